@@ -1,5 +1,8 @@
-use clap::{ArgAction, Parser, Subcommand};
-use std::path::PathBuf;
+use crate::config::ConfigKey;
+use aws_sdk_sfn::types::ExecutionStatus;
+use clap::{ArgAction, Parser, Subcommand, ValueEnum};
+use serde::Serialize;
+use std::{num::NonZeroUsize, path::PathBuf, time::Duration};
 
 #[derive(Parser)]
 #[command(
@@ -16,6 +19,18 @@ pub(crate) struct Args {
     #[arg(short, long, global = true, help_heading = "GLOBAL OPTIONS")]
     pub(crate) region: Option<String>,
 
+    /// The named environment from the config file to use.
+    #[arg(short, long = "env", global = true, help_heading = "GLOBAL OPTIONS")]
+    pub(crate) environment: Option<String>,
+
+    /// Override the ARN of the pipeline's state machine.
+    #[arg(long, global = true, help_heading = "GLOBAL OPTIONS")]
+    pub(crate) state_machine_arn: Option<String>,
+
+    /// Override the S3 bucket audio files are stored in.
+    #[arg(short, long, global = true, help_heading = "GLOBAL OPTIONS")]
+    pub(crate) bucket: Option<String>,
+
     /// Increase the verbosity of the output. Warning: this can affect performance.
     #[arg(
         short,
@@ -26,6 +41,17 @@ pub(crate) struct Args {
     )]
     pub(crate) verbose: u8,
 
+    /// How to print results. JSON and YAML go to stdout, with everything else on stderr.
+    #[arg(
+        short,
+        long,
+        value_enum,
+        default_value = "table",
+        global = true,
+        help_heading = "GLOBAL OPTIONS"
+    )]
+    pub(crate) output: OutputFormat,
+
     #[command(subcommand)]
     pub(crate) command: Commands,
 }
@@ -37,6 +63,61 @@ pub(crate) enum Commands {
     Get(GetCommands),
     /// Run the pipeline on a meeting audio file.
     Process(ProcessArgs),
+    /// Read and write the config file.
+    #[command(subcommand)]
+    Config(ConfigCommands),
+    /// Process recordings as they appear in a directory.
+    Watch(WatchArgs),
+    /// Stop a running job.
+    Cancel {
+        /// The job: an execution name or ARN, or the object key of its audio file.
+        job: String,
+        /// Don't ask for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+    },
+    /// Start a job over as a new execution with its original input.
+    ///
+    /// This starts a new execution from the first step, with a new name. Failed executions
+    /// aren't redriven from the step that failed, as the Step Functions SDK this CLI is built
+    /// with doesn't support it, so work that already finished is done again.
+    Retry {
+        /// The job: an execution name or ARN, or the object key of its audio file.
+        job: String,
+        /// Don't ask for confirmation.
+        #[arg(short, long)]
+        yes: bool,
+    },
+}
+
+#[derive(Subcommand)]
+pub(crate) enum ConfigCommands {
+    /// Show the settings in effect and where each one comes from.
+    Show,
+    /// Print the path of the config file.
+    Path,
+    /// Print a setting from the config file.
+    Get {
+        /// The setting to print.
+        key: ConfigKey,
+    },
+    /// Save a setting to the config file.
+    Set {
+        /// The setting to save.
+        key: ConfigKey,
+        /// The value to save.
+        value: String,
+    },
+    /// Remove a setting from the config file.
+    Unset {
+        /// The setting to remove.
+        key: ConfigKey,
+    },
+    /// Use an environment by default.
+    Use {
+        /// The name of the environment.
+        environment: String,
+    },
 }
 
 #[derive(Subcommand)]
@@ -45,49 +126,265 @@ pub(crate) enum GetCommands {
     Buckets,
     /// Get the status of a transcript job for an audio file.
     Status {
-        /// The S3 bucket in which the audio file was stored.
-        bucket: String,
-        /// The object key for the audio file.
-        key: String,
+        /// The job: an execution name or ARN, or the object key of the audio file.
+        job: String,
+        /// Only consider runs with this status.
+        #[arg(long, value_enum)]
+        status: Option<StatusFilter>,
+        /// Only consider runs started within this long, such as 30m, 12h or 7d.
+        #[arg(long, value_parser = parse_duration)]
+        since: Option<Duration>,
+    },
+    /// List past and running jobs, newest first.
+    Jobs {
+        /// Only list jobs with this status.
+        #[arg(long, value_enum)]
+        status: Option<StatusFilter>,
+        /// Only list jobs started within this long, such as 30m, 12h or 7d.
+        #[arg(long, value_parser = parse_duration)]
+        since: Option<Duration>,
+        /// Only list jobs whose object key contains this text.
+        #[arg(long)]
+        key_contains: Option<String>,
+        /// The column to sort by.
+        #[arg(long, value_enum, default_value = "started")]
+        sort: JobSort,
+        /// Reverse the sort order.
+        #[arg(long)]
+        reverse: bool,
+        /// The most jobs to list, counting from the newest.
+        #[arg(long, default_value = "50")]
+        limit: usize,
     },
     /// Get the final report for an audio file.
     Report {
-        /// The S3 bucket in which the audio file was stored.
-        bucket: String,
-        /// The object key for the audio file.
-        key: String,
+        /// The job: an execution name or ARN, or the object key of the audio file.
+        job: String,
         /// Where to optionally save the report.
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+        #[arg(short, long, value_name = "PATH")]
+        save: Option<PathBuf>,
     },
     /// Get the transcript for an audio file.
     Transcript {
-        /// The S3 bucket in which the audio file was stored.
-        bucket: String,
-        /// The object key for the audio file.
-        key: String,
+        /// The job: an execution name or ARN, or the object key of the audio file.
+        job: String,
         /// Where to optionally save the transcript.
-        #[arg(short, long)]
-        output: Option<PathBuf>,
+        #[arg(short, long, value_name = "PATH")]
+        save: Option<PathBuf>,
     },
 }
 
 #[derive(Parser)]
 pub(crate) struct ProcessArgs {
-    /// The S3 bucket in which to store the audio file.
-    pub(crate) bucket: String,
-    /// The object key for the audio file.
-    pub(crate) file: PathBuf,
-    /// The language of the audio file.
+    /// The audio file to upload.
+    #[arg(required_unless_present = "batch", conflicts_with = "batch")]
+    pub(crate) file: Option<PathBuf>,
+    /// The exact object key to upload the file to, instead of deriving one.
+    #[arg(long, conflicts_with_all = ["batch", "prefix", "key_scheme"])]
+    pub(crate) key: Option<String>,
+    #[command(flatten)]
+    pub(crate) keys: KeyOptions,
+    /// Process many recordings: directories of audio files, files, or patterns such as
+    /// "recordings/*.mp3".
+    #[arg(long, num_args = 1.., value_name = "DIR|GLOB")]
+    pub(crate) batch: Vec<String>,
+    /// How many files of a batch are uploaded and started at once.
+    #[arg(long, default_value = "4")]
+    pub(crate) concurrency: NonZeroUsize,
+    /// The language of the audio file. Defaults to the configured language, then en-US.
     #[arg(long)]
     pub(crate) language: Option<String>,
     /// Await the completion of the pipeline run.
     #[arg(long)]
     pub(crate) wait: bool,
     /// Where to optionally save the transcript.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "batch")]
     pub(crate) transcript_output: Option<PathBuf>,
     /// Where to optionally save the report.
-    #[arg(long)]
+    #[arg(long, conflicts_with = "batch")]
     pub(crate) report_output: Option<PathBuf>,
+    /// How per-chunk sentiment is combined into the overall verdict.
+    #[arg(long, value_enum)]
+    pub(crate) sentiment_aggregation: Option<SentimentAggregation>,
+    /// A JSON file mapping canonical entity names to their variants.
+    #[arg(long)]
+    pub(crate) entity_aliases: Option<PathBuf>,
+    /// A JSON report config choosing sections, thresholds and display styles.
+    #[arg(long)]
+    pub(crate) report_config: Option<PathBuf>,
+}
+
+#[derive(Parser)]
+pub(crate) struct WatchArgs {
+    /// The directory recordings are saved to.
+    pub(crate) dir: PathBuf,
+    /// Where to save reports and transcripts. Defaults to a folder next to the directory, such
+    /// as recordings-reports for recordings.
+    #[arg(short, long, value_name = "DIR")]
+    pub(crate) save: Option<PathBuf>,
+    /// How long a file's size must hold before it counts as finished, such as 30s or 2m.
+    #[arg(long, default_value = "30s", value_parser = parse_duration)]
+    pub(crate) settle: Duration,
+    /// How many recordings are uploaded and followed to the end at once.
+    #[arg(long, default_value = "4")]
+    pub(crate) concurrency: NonZeroUsize,
+    /// The language of the recordings. Defaults to the configured language, then en-US.
+    #[arg(long)]
+    pub(crate) language: Option<String>,
+    #[command(flatten)]
+    pub(crate) keys: KeyOptions,
+}
+
+/// How the object key an audio file is uploaded to is chosen. Transcripts and reports are
+/// stored next to it, so files with the same key overwrite each other's results.
+#[derive(Clone, clap::Args)]
+pub(crate) struct KeyOptions {
+    /// A folder to upload to, such as meetings/ or team/2024/.
+    #[arg(long)]
+    pub(crate) prefix: Option<String>,
+    /// How object keys are derived from file names.
+    #[arg(long, value_enum, default_value = "name")]
+    pub(crate) key_scheme: KeyScheme,
+}
+
+#[derive(Clone, Copy, Default, PartialEq, ValueEnum)]
+pub(crate) enum OutputFormat {
+    /// Tables and messages for people.
+    #[default]
+    Table,
+    /// JSON objects, one document per result.
+    Json,
+    /// YAML documents, one per result.
+    Yaml,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum KeyScheme {
+    /// The file name, such as meeting.m4a.
+    Name,
+    /// The date the file was last modified and its name, such as 2024-06-01/meeting.m4a.
+    Date,
+    /// A hash of the file's contents and its name, such as 3f2a9c0d1b7e4a56/meeting.m4a.
+    Hash,
+}
+
+#[derive(Clone, Copy, ValueEnum, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SentimentAggregation {
+    /// Every chunk counts equally.
+    Majority,
+    /// Chunks are weighted by their length.
+    LengthWeighted,
+    /// Chunks are weighted by their length and the confidence of their label.
+    ConfidenceWeighted,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum StatusFilter {
+    Running,
+    Succeeded,
+    Failed,
+    TimedOut,
+    Aborted,
+}
+
+impl From<StatusFilter> for ExecutionStatus {
+    fn from(status: StatusFilter) -> Self {
+        match status {
+            StatusFilter::Running => ExecutionStatus::Running,
+            StatusFilter::Succeeded => ExecutionStatus::Succeeded,
+            StatusFilter::Failed => ExecutionStatus::Failed,
+            StatusFilter::TimedOut => ExecutionStatus::TimedOut,
+            StatusFilter::Aborted => ExecutionStatus::Aborted,
+        }
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+pub(crate) enum JobSort {
+    /// Newest first.
+    Started,
+    /// Most recently finished first.
+    Stopped,
+    /// Longest first.
+    Duration,
+    /// By object key.
+    Key,
+    /// By status.
+    Status,
+}
+
+/// Parses a duration such as `90s`, `30m`, `12h` or `7d`. Zero is rejected, as neither a time
+/// window nor a settle time can be empty.
+fn parse_duration(value: &str) -> Result<Duration, String> {
+    let split = value
+        .find(|c: char| !c.is_ascii_digit())
+        .unwrap_or(value.len());
+    let (amount, unit) = value.split_at(split);
+    let amount: u64 = amount
+        .parse()
+        .map_err(|_| format!("expected a number followed by s, m, h or d, got {}", value))?;
+    if amount == 0 {
+        return Err(format!(
+            "expected a duration longer than zero, got {}",
+            value
+        ));
+    }
+
+    let seconds = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 60 * 60,
+        "d" => 24 * 60 * 60,
+        _ => return Err(format!("unknown unit in {}, expected s, m, h or d", value)),
+    };
+
+    amount
+        .checked_mul(seconds)
+        .map(Duration::from_secs)
+        .ok_or_else(|| format!("{} is too long", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_each_unit() {
+        assert_eq!(parse_duration("90s"), Ok(Duration::from_secs(90)));
+        assert_eq!(parse_duration("30m"), Ok(Duration::from_secs(30 * 60)));
+        assert_eq!(parse_duration("12h"), Ok(Duration::from_secs(12 * 60 * 60)));
+        assert_eq!(
+            parse_duration("7d"),
+            Ok(Duration::from_secs(7 * 24 * 60 * 60))
+        );
+    }
+
+    #[test]
+    fn rejects_missing_and_unknown_units() {
+        assert!(parse_duration("30").is_err());
+        assert!(parse_duration("30w").is_err());
+        assert!(parse_duration("30 m").is_err());
+        assert!(parse_duration("30mm").is_err());
+        assert!(parse_duration("30M").is_err());
+    }
+
+    #[test]
+    fn rejects_missing_and_zero_amounts() {
+        assert!(parse_duration("").is_err());
+        assert!(parse_duration("m").is_err());
+        assert!(parse_duration("-5m").is_err());
+        assert!(parse_duration("0s").is_err());
+        assert!(parse_duration("00d").is_err());
+    }
+
+    #[test]
+    fn rejects_durations_that_overflow() {
+        assert!(parse_duration("999999999999999999d").is_err());
+        assert!(parse_duration("99999999999999999999s").is_err());
+        assert_eq!(
+            parse_duration(&format!("{}s", u64::MAX)),
+            Ok(Duration::from_secs(u64::MAX))
+        );
+    }
 }
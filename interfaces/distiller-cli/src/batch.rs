@@ -0,0 +1,222 @@
+use anyhow::{bail, Context, Result};
+use glob::{MatchOptions, Pattern};
+use std::{
+    collections::BTreeSet,
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// The media formats Amazon Transcribe accepts, which are picked up when a batch names a directory.
+const AUDIO_EXTENSIONS: [&str; 8] = ["amr", "flac", "m4a", "mp3", "mp4", "ogg", "wav", "webm"];
+const WILDCARDS: [char; 3] = ['*', '?', '['];
+/// Like a shell, wildcards don't match a leading dot.
+const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: true,
+};
+
+pub(crate) fn is_audio(path: &Path) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .is_some_and(|ext| AUDIO_EXTENSIONS.contains(&ext.to_ascii_lowercase().as_str()))
+}
+
+/// The files in a directory whose names pass `include`, sorted by path.
+fn files_in(dir: &Path, include: impl Fn(&Path, &str) -> bool) -> Result<Vec<PathBuf>> {
+    let entries =
+        fs::read_dir(dir).with_context(|| format!("Failed to read directory: {:?}", dir))?;
+
+    let mut files = Vec::new();
+    for entry in entries {
+        let path = entry?.path();
+        let name = path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .to_string();
+        if path.is_file() && include(&path, &name) {
+            files.push(path);
+        }
+    }
+    files.sort();
+    Ok(files)
+}
+
+/// The files matching a glob pattern, whose wildcards may be in any part of the path. Each part
+/// is matched against the entries of the directories the parts before it matched.
+fn files_matching(pattern: &Path) -> Result<Vec<PathBuf>> {
+    let mut paths = vec![PathBuf::new()];
+
+    for part in pattern.components() {
+        let part = part.as_os_str();
+        let text = part.to_string_lossy();
+        if !text.contains(WILDCARDS) {
+            paths = paths.into_iter().map(|path| path.join(part)).collect();
+            continue;
+        }
+
+        let part = Pattern::new(&text)?;
+        let mut matched = Vec::new();
+        for dir in paths
+            .iter()
+            .filter(|dir| dir.as_os_str().is_empty() || dir.is_dir())
+        {
+            let read_from = if dir.as_os_str().is_empty() {
+                Path::new(".")
+            } else {
+                dir.as_path()
+            };
+            for entry in fs::read_dir(read_from)? {
+                let name = entry?.file_name();
+                if part.matches_with(&name.to_string_lossy(), MATCH_OPTIONS) {
+                    matched.push(dir.join(name));
+                }
+            }
+        }
+        paths = matched;
+    }
+
+    paths.retain(|path| path.is_file());
+    paths.sort();
+    Ok(paths)
+}
+
+/// Expands the `--batch` arguments into the recordings to process. Each one is a file, a
+/// directory whose audio files are all taken, or a glob pattern such as `recordings/*.mp3` or
+/// `team/*/2024-??-*.m4a`. Unquoted patterns already expanded by the shell work the same.
+pub(crate) fn find_recordings(sources: &[String]) -> Result<Vec<PathBuf>> {
+    let mut recordings = BTreeSet::new();
+
+    for source in sources {
+        let path = Path::new(source);
+
+        if path.is_dir() {
+            recordings.extend(files_in(path, |file, name| {
+                !name.starts_with('.') && is_audio(file)
+            })?);
+        } else if source.contains(WILDCARDS) {
+            let matched = files_matching(path)
+                .with_context(|| format!("Failed to expand pattern: {}", source))?;
+            if matched.is_empty() {
+                bail!("No files match {}", source);
+            }
+            recordings.extend(matched);
+        } else if path.is_file() {
+            recordings.insert(path.to_path_buf());
+        } else {
+            bail!("File does not exist: {:?}", path);
+        }
+    }
+
+    if recordings.is_empty() {
+        bail!("No audio files found in {}", sources.join(", "));
+    }
+
+    Ok(recordings.into_iter().collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    /// A directory holding empty files at the given relative paths.
+    fn recordings_dir(files: &[&str]) -> TempDir {
+        let dir = TempDir::new().unwrap();
+        for file in files {
+            let path = dir.path().join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, b"").unwrap();
+        }
+        dir
+    }
+
+    /// The recordings found for patterns under `dir`, relative to it.
+    fn find(dir: &TempDir, patterns: &[&str]) -> Result<Vec<String>> {
+        let sources: Vec<String> = patterns
+            .iter()
+            .map(|pattern| dir.path().join(pattern).to_string_lossy().to_string())
+            .collect();
+        Ok(find_recordings(&sources)?
+            .iter()
+            .map(|path| {
+                path.strip_prefix(dir.path())
+                    .unwrap()
+                    .to_string_lossy()
+                    .to_string()
+            })
+            .collect())
+    }
+
+    #[test]
+    fn star_matches_any_run_of_characters() {
+        let dir = recordings_dir(&["a-b-c.mp3", "a-c.mp3", "a-b-d.mp3", "c.mp3.txt"]);
+        assert_eq!(find(&dir, &["*-c.mp3"]).unwrap(), ["a-b-c.mp3", "a-c.mp3"]);
+        assert_eq!(
+            find(&dir, &["a*b*.mp3"]).unwrap(),
+            ["a-b-c.mp3", "a-b-d.mp3"]
+        );
+        assert_eq!(find(&dir, &["*"]).unwrap().len(), 4);
+    }
+
+    #[test]
+    fn question_mark_matches_one_character() {
+        let dir = recordings_dir(&["take1.mp3", "take2.mp3", "take10.mp3", "take.mp3"]);
+        assert_eq!(
+            find(&dir, &["take?.mp3"]).unwrap(),
+            ["take1.mp3", "take2.mp3"]
+        );
+    }
+
+    #[test]
+    fn paths_without_wildcards_name_one_file() {
+        let dir = recordings_dir(&["meeting.mp3", "notes.txt"]);
+        assert_eq!(find(&dir, &["meeting.mp3"]).unwrap(), ["meeting.mp3"]);
+        // Named files are taken whatever their extension
+        assert_eq!(find(&dir, &["notes.txt"]).unwrap(), ["notes.txt"]);
+        assert!(find(&dir, &["missing.mp3"]).is_err());
+    }
+
+    #[test]
+    fn wildcards_match_directory_parts() {
+        let dir = recordings_dir(&["team/a/sync.mp3", "team/b/sync.mp3", "team/b/other.mp3"]);
+        assert_eq!(
+            find(&dir, &["team/*/sync.mp3"]).unwrap(),
+            ["team/a/sync.mp3", "team/b/sync.mp3"]
+        );
+        // A star doesn't cross directories
+        assert!(find(&dir, &["team/*.mp3"]).is_err());
+    }
+
+    #[test]
+    fn patterns_matching_nothing_fail() {
+        let dir = recordings_dir(&["meeting.mp3"]);
+        let err = find(&dir, &["*.wav"]).unwrap_err();
+        assert!(err.to_string().starts_with("No files match"));
+        assert!(find(&dir, &["missing/*.mp3"]).is_err());
+    }
+
+    #[test]
+    fn hidden_files_need_a_literal_dot() {
+        let dir = recordings_dir(&["meeting.mp3", ".draft.mp3"]);
+        assert_eq!(find(&dir, &["*.mp3"]).unwrap(), ["meeting.mp3"]);
+        assert!(find(&dir, &["?draft.mp3"]).is_err());
+        assert_eq!(find(&dir, &[".*.mp3"]).unwrap(), [".draft.mp3"]);
+    }
+
+    #[test]
+    fn directories_take_visible_audio_files() {
+        let dir = recordings_dir(&["b.MP3", "a.wav", ".hidden.mp3", "notes.txt", "sub/c.mp3"]);
+        assert_eq!(find(&dir, &[""]).unwrap(), ["a.wav", "b.MP3"]);
+    }
+
+    #[test]
+    fn sources_are_combined_without_duplicates() {
+        let dir = recordings_dir(&["a.mp3", "b.mp3"]);
+        assert_eq!(
+            find(&dir, &["*.mp3", "a.mp3", ""]).unwrap(),
+            ["a.mp3", "b.mp3"]
+        );
+    }
+}
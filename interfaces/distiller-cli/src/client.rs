@@ -1,35 +1,80 @@
 use crate::{
-    args::ProcessArgs,
+    args::{ProcessArgs, SentimentAggregation},
+    batch::find_recordings,
+    config::Settings,
     display::{
-        print_divider, print_error, print_header, print_success, print_table_row, truncate_arn,
+        confirm, print_aligned_row, print_divider, print_error, print_header, print_note,
+        print_success, print_table_row, print_warning, truncate_arn,
     },
+    executions::{
+        execution_arn, execution_index_key, execution_name, has_derived_shape, is_derived_name,
+        name_from_arn, ExecutionFilter, ExecutionIndex,
+    },
+    jobs::JobSummary,
+    keys::object_key,
+    output::{is_structured, print_output, CommandError},
+    progress::ExecutionProgress,
+    upload::{upload_file, upload_style},
 };
 use anyhow::{bail, Context, Result};
-use aws_sdk_s3::config::Region;
 use aws_sdk_s3::Client as S3Client;
-use aws_sdk_sfn::{types::ExecutionStatus, Client as SfnClient};
+use aws_sdk_s3::{config::Region, primitives::DateTimeFormat};
+use aws_sdk_sfn::{
+    error::SdkError,
+    operation::describe_execution::DescribeExecutionOutput,
+    types::{ExecutionStatus, HistoryEvent},
+    Client as SfnClient,
+};
 use console::{style, StyledObject};
-use indicatif::{ProgressBar, ProgressStyle};
-use std::{path::PathBuf, time::Duration};
+use futures::{future, stream, StreamExt, TryStreamExt};
+use indicatif::{MultiProgress, ProgressBar, ProgressStyle};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    time::Duration,
+};
 use tokio::{fs, time::sleep};
 use tracing::debug;
 
 const DEFAULT_LANGUAGE: &str = "en-US";
-const STATE_MACHINE_ARN: &str =
-    "arn:aws:states:us-east-1:816069165876:stateMachine:AudioProcessingPipeline";
 const POLLING_INTERVAL: u64 = 5;
 const SPINNER_INTERVAL: u64 = 100;
 const SPINNER_CHARS: &str = "⠋⠙⠹⠸⠼⠴⠦⠧⠇⠏";
 const DIVIDER_WIDTH: usize = 60;
+const DESCRIBE_CONCURRENCY: usize = 10;
+/// Shown for an execution whose description has no status.
+pub(crate) const UNKNOWN_STATUS: &str = "UNKNOWN";
+
+/// Optional settings forwarded to the pipeline in the execution input.
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct ExecutionOptions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    sentiment_aggregation: Option<SentimentAggregation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    entity_aliases_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report_config_key: Option<String>,
+}
+
+/// A run of the pipeline named on the command line, resolved to the audio file it processed.
+pub(crate) struct Job {
+    pub(crate) bucket: String,
+    pub(crate) key: String,
+    /// Set when the job was named by its execution rather than its key.
+    execution_arn: Option<String>,
+}
 
 pub(crate) struct Client {
     s3_client: S3Client,
     sfn_client: SfnClient,
+    state_machine_arn: Option<String>,
 }
 
 impl Client {
-    pub(crate) async fn new(profile: Option<String>, region: Option<String>) -> Self {
-        let config = match (profile, region) {
+    pub(crate) async fn new(settings: &Settings) -> Self {
+        let config = match (settings.profile.clone(), settings.region.clone()) {
             (Some(profile), Some(region)) => aws_config::from_env()
                 .profile_name(profile)
                 .region(Region::new(region)),
@@ -45,9 +90,17 @@ impl Client {
         Self {
             s3_client: S3Client::new(&config),
             sfn_client: SfnClient::new(&config),
+            state_machine_arn: settings.state_machine_arn.clone(),
         }
     }
 
+    fn state_machine_arn(&self) -> Result<&str> {
+        self.state_machine_arn.as_deref().context(
+            "No state machine ARN configured; pass --state-machine-arn or set one with \
+             `distiller config set state-machine-arn <arn>`",
+        )
+    }
+
     pub(crate) async fn list_buckets(&self) -> Result<()> {
         let buckets = self
             .s3_client
@@ -56,6 +109,23 @@ impl Client {
             .await
             .context("Failed to list buckets")?;
 
+        if is_structured() {
+            let buckets: Vec<_> = buckets
+                .buckets()
+                .into_iter()
+                .flatten()
+                .map(|bucket| {
+                    serde_json::json!({
+                        "name": bucket.name(),
+                        "created": bucket
+                            .creation_date()
+                            .and_then(|date| date.fmt(DateTimeFormat::DateTime).ok()),
+                    })
+                })
+                .collect();
+            return print_output(&buckets);
+        }
+
         print_header("Available Buckets");
         print_divider();
 
@@ -70,26 +140,70 @@ impl Client {
         Ok(())
     }
 
-    pub(crate) async fn get_status(&self, bucket: &str, key: &str) -> Result<()> {
-        let execution_arn = self.find_execution(key).await?;
-        let execution = self
+    /// Resolves a job identifier: an execution ARN or name, whose input gives the audio file,
+    /// or otherwise the object key of the audio file in the bucket. A name is only taken for an
+    /// execution when its hash matches the file the execution processed.
+    pub(crate) async fn resolve_job(&self, bucket: &str, id: &str) -> Result<Job> {
+        let by_key = || Job {
+            bucket: bucket.to_string(),
+            key: id.to_string(),
+            execution_arn: None,
+        };
+        let is_name = !id.starts_with("arn:");
+        let execution_arn = if !is_name {
+            id.to_string()
+        } else if has_derived_shape(id) {
+            execution_arn(self.state_machine_arn()?, id)
+        } else {
+            return Ok(by_key());
+        };
+
+        let execution = match self
             .sfn_client
             .describe_execution()
             .execution_arn(&execution_arn)
             .send()
             .await
-            .context("Failed to get execution description")?;
+        {
+            Ok(execution) => execution,
+            Err(SdkError::ServiceError(err)) if err.err().is_execution_does_not_exist() => {
+                if is_name {
+                    return Ok(by_key());
+                }
+                bail!(CommandError::NotFound(format!(
+                    "No execution found for job: {}",
+                    id
+                )))
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to get execution for job: {}", id))
+            }
+        };
+        let input: serde_json::Value = execution
+            .input()
+            .and_then(|input| serde_json::from_str(input).ok())
+            .context("Execution input is not valid JSON")?;
 
-        print_header("Status Report");
-        print_divider();
-        print_table_row("File:", key);
-        print_table_row("Bucket:", bucket);
-        print_table_row(
-            "Status:",
-            get_status_style(execution.status().expect("execution should have status")).to_string(),
-        );
-        print_table_row("Execution:", truncate_arn(&execution_arn));
-        print_divider();
+        let job = Job {
+            bucket: input["bucket"].as_str().unwrap_or(bucket).to_string(),
+            key: input["key"]
+                .as_str()
+                .context("Execution input has no key")?
+                .to_string(),
+            execution_arn: Some(execution_arn),
+        };
+        if is_name && !is_derived_name(id, &job.bucket, &job.key) {
+            return Ok(by_key());
+        }
+        Ok(job)
+    }
+
+    pub(crate) async fn get_status(&self, job: &Job, filter: ExecutionFilter) -> Result<()> {
+        let execution = self.describe_job(job, &filter).await?;
+        if is_structured() {
+            return print_output(&JobSummary::from_execution(&execution));
+        }
+        print_execution("Status Report", job, &execution);
 
         if let Some(error) = execution.error() {
             print_error(error);
@@ -99,113 +213,768 @@ impl Client {
         Ok(())
     }
 
-    async fn find_execution(&self, file_key: &str) -> Result<String> {
-        let executions = self
+    /// Stops a running job after confirming.
+    pub(crate) async fn cancel(&self, job: &Job, yes: bool) -> Result<()> {
+        let filter = ExecutionFilter {
+            status: Some(ExecutionStatus::Running),
+            ..Default::default()
+        };
+        let execution = self.describe_job(job, &filter).await?;
+        print_execution("Cancel Job", job, &execution);
+
+        if execution.status() != Some(&ExecutionStatus::Running) {
+            bail!("Only running jobs can be cancelled");
+        }
+        let execution_arn = execution.execution_arn().unwrap_or_default();
+        if !yes && !confirm("Stop this execution?")? {
+            print_note("Left the job running");
+            return print_output(&serde_json::json!({
+                "name": name_from_arn(execution_arn),
+                "execution_arn": execution_arn,
+                "stopped": false,
+            }));
+        }
+
+        self.sfn_client
+            .stop_execution()
+            .execution_arn(execution_arn)
+            .error("Cancelled")
+            .cause("Stopped from the distiller CLI")
+            .send()
+            .await
+            .context("Failed to stop execution")?;
+
+        print_success(format!("Stopped job {}", name_from_arn(execution_arn)));
+
+        print_output(&serde_json::json!({
+            "name": name_from_arn(execution_arn),
+            "execution_arn": execution_arn,
+            "stopped": true,
+        }))
+    }
+
+    /// Runs a job that didn't succeed again from the start, with its original input, after
+    /// confirming. Redriving from the failed state needs a newer Step Functions SDK.
+    pub(crate) async fn retry(&self, job: &Job, yes: bool) -> Result<()> {
+        let execution = self.describe_job(job, &ExecutionFilter::default()).await?;
+        print_execution("Retry Job", job, &execution);
+
+        match execution.status() {
+            Some(ExecutionStatus::Running) => {
+                bail!("The job is still running; cancel it first to start it again")
+            }
+            Some(ExecutionStatus::Succeeded) => {
+                print_warning("This job already succeeded; retrying will run it again")
+            }
+            _ => {}
+        }
+        let retried = execution.execution_arn().unwrap_or_default();
+        if !yes && !confirm("Start a new execution with the same input?")? {
+            print_note("Started nothing");
+            return print_output(&serde_json::json!({
+                "retried": retried,
+                "started": false,
+            }));
+        }
+
+        let started = self
+            .sfn_client
+            .start_execution()
+            .state_machine_arn(self.state_machine_arn()?)
+            .name(execution_name(&job.bucket, &job.key))
+            .input(execution.input().context("Execution has no input")?)
+            .send()
+            .await
+            .context("Failed to start execution")?;
+
+        let execution_arn = started.execution_arn().unwrap_or_default();
+        self.index_execution(&job.bucket, &job.key, execution_arn)
+            .await;
+        print_success(format!("Started job {}", name_from_arn(execution_arn)));
+
+        print_output(&serde_json::json!({
+            "retried": retried,
+            "started": true,
+            "name": name_from_arn(execution_arn),
+            "execution_arn": execution_arn,
+        }))
+    }
+
+    /// Describes the execution a job was named by, or the most recent one for its file: the one
+    /// in the file's execution index when it passes the filter, and otherwise the newest found
+    /// by searching the execution history.
+    async fn describe_job(
+        &self,
+        job: &Job,
+        filter: &ExecutionFilter,
+    ) -> Result<DescribeExecutionOutput> {
+        let execution_arn = match &job.execution_arn {
+            Some(execution_arn) => execution_arn.clone(),
+            None => match self.indexed_execution(&job.bucket, &job.key, filter).await {
+                Some(execution) => return Ok(execution),
+                None => self.find_execution(&job.bucket, &job.key, filter).await?,
+            },
+        };
+
+        self.sfn_client
+            .describe_execution()
+            .execution_arn(&execution_arn)
+            .send()
+            .await
+            .context("Failed to get execution description")
+    }
+
+    /// The execution a file's index names, when it's still there, belongs to this state machine
+    /// and passes the filter. The index only names the newest run, and files processed by older
+    /// versions of the CLI have none, so any miss falls back to searching.
+    async fn indexed_execution(
+        &self,
+        bucket: &str,
+        key: &str,
+        filter: &ExecutionFilter,
+    ) -> Option<DescribeExecutionOutput> {
+        let index = self
+            .get_object(bucket, &execution_index_key(key))
+            .await
+            .ok()?;
+        let index: ExecutionIndex = serde_json::from_slice(&index).ok()?;
+
+        let execution = self
+            .sfn_client
+            .describe_execution()
+            .execution_arn(&index.execution_arn)
+            .send()
+            .await
+            .ok()?;
+
+        let cutoff = filter.cutoff();
+        (execution.state_machine_arn() == self.state_machine_arn.as_deref()
+            && filter
+                .status
+                .as_ref()
+                .is_none_or(|status| execution.status() == Some(status))
+            && cutoff.is_none_or(|cutoff| {
+                execution
+                    .start_date()
+                    .is_some_and(|start| start.secs() >= cutoff)
+            }))
+        .then_some(execution)
+    }
+
+    /// Records a file's newest execution in its index. The index only speeds up lookups, so a
+    /// failure to write it is logged rather than failing the run.
+    async fn index_execution(&self, bucket: &str, key: &str, execution_arn: &str) {
+        let index = ExecutionIndex {
+            execution_arn: execution_arn.to_string(),
+        };
+        let Ok(body) = serde_json::to_vec(&index) else {
+            return;
+        };
+
+        if let Err(err) = self
+            .s3_client
+            .put_object()
+            .bucket(bucket)
+            .key(execution_index_key(key))
+            .content_type("application/json")
+            .body(body.into())
+            .send()
+            .await
+        {
+            tracing::warn!("Failed to index execution {}: {}", execution_arn, err);
+        }
+    }
+
+    /// Finds the most recent execution for a file. Runs are named after the bucket and key, so
+    /// they are matched while paging through the execution list; runs from before that are
+    /// matched by reading their input, a bounded number at a time.
+    async fn find_execution(
+        &self,
+        bucket: &str,
+        key: &str,
+        filter: &ExecutionFilter,
+    ) -> Result<String> {
+        let cutoff = filter.cutoff();
+
+        let mut executions = self
             .sfn_client
             .list_executions()
-            .state_machine_arn(STATE_MACHINE_ARN)
+            .state_machine_arn(self.state_machine_arn()?)
+            .set_status_filter(filter.status.clone())
+            .into_paginator()
+            .items()
+            .send();
+
+        let mut unnamed = Vec::new();
+        while let Some(execution) = executions.next().await {
+            let execution = execution.context("Failed to list executions")?;
+
+            // Executions are listed newest first
+            if cutoff.is_some_and(|cutoff| {
+                execution
+                    .start_date()
+                    .is_some_and(|start| start.secs() < cutoff)
+            }) {
+                break;
+            }
+
+            let name = execution.name().unwrap_or_default();
+            let execution_arn = execution.execution_arn().unwrap_or_default();
+            if is_derived_name(name, bucket, key) {
+                return Ok(execution_arn.to_string());
+            }
+            if !has_derived_shape(name) {
+                unnamed.push(execution_arn.to_string());
+            }
+        }
+
+        let found = stream::iter(unnamed)
+            .map(|execution_arn| async move {
+                let input = self
+                    .sfn_client
+                    .describe_execution()
+                    .execution_arn(&execution_arn)
+                    .send()
+                    .await
+                    .ok()?
+                    .input()
+                    .and_then(|input| serde_json::from_str::<serde_json::Value>(input).ok())?;
+
+                (input["key"].as_str() == Some(key)
+                    && input["bucket"].as_str().is_none_or(|b| b == bucket))
+                .then_some(execution_arn)
+            })
+            .buffered(DESCRIBE_CONCURRENCY)
+            .filter_map(future::ready)
+            .next()
+            .await;
+
+        found.ok_or_else(|| {
+            CommandError::NotFound(format!("No execution found for file: {}", key)).into()
+        })
+    }
+
+    /// Lists executions newest first, reading each one's input for the file it processed, a
+    /// bounded number at a time, until `limit` jobs pass the filters.
+    pub(crate) async fn list_jobs(
+        &self,
+        filter: &ExecutionFilter,
+        key_contains: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<JobSummary>> {
+        let cutoff = filter.cutoff();
+
+        self.sfn_client
+            .list_executions()
+            .state_machine_arn(self.state_machine_arn()?)
+            .set_status_filter(filter.status.clone())
+            .into_paginator()
+            .items()
             .send()
+            // Executions are listed newest first
+            .try_take_while(|execution| {
+                future::ok(cutoff.is_none_or(|cutoff| {
+                    execution
+                        .start_date()
+                        .is_none_or(|start| start.secs() >= cutoff)
+                }))
+            })
+            .map(|execution| async move {
+                let execution = execution.context("Failed to list executions")?;
+                let description = self
+                    .sfn_client
+                    .describe_execution()
+                    .execution_arn(execution.execution_arn().unwrap_or_default())
+                    .send()
+                    .await
+                    .context("Failed to get execution description")?;
+
+                anyhow::Ok(JobSummary::from_execution(&description))
+            })
+            .buffered(DESCRIBE_CONCURRENCY)
+            .try_filter(|job| {
+                future::ready(
+                    key_contains.is_none_or(|text| {
+                        job.key.as_deref().is_some_and(|key| key.contains(text))
+                    }),
+                )
+            })
+            .take(limit)
+            .try_collect()
             .await
-            .context("Failed to list executions")?;
+    }
 
-        for execution in executions.executions().unwrap_or_default() {
-            if let Some(input) = self
-                .sfn_client
-                .describe_execution()
-                .execution_arn(execution.execution_arn().unwrap_or_default())
-                .send()
-                .await
-                .ok()
-                .and_then(|e| e.input().map(String::from))
-            {
-                if let Ok(input) = serde_json::from_str::<serde_json::Value>(&input) {
-                    if input["key"].as_str() == Some(file_key) {
-                        return Ok(execution.execution_arn().unwrap_or_default().to_string());
-                    }
+    pub(crate) async fn process_file(&self, bucket: &str, args: ProcessArgs) -> Result<()> {
+        let file = args.file.clone().context("No file given")?;
+        if !file.exists() {
+            bail!("File does not exist: {:?}", file);
+        }
+
+        let key = match &args.key {
+            Some(key) => key.clone(),
+            None => object_key(&file, &args.keys).await?,
+        };
+        let language = args
+            .language
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+
+        print_header("Processing Job");
+        print_divider();
+        print_table_row(
+            "File:",
+            file.file_name().unwrap_or_default().to_string_lossy(),
+        );
+        print_table_row("Key:", &key);
+        print_table_row("Bucket:", bucket);
+        print_table_row("Language:", &language);
+        print_divider();
+
+        let progress = ProgressBar::new(0).with_style(upload_style()?);
+        progress.set_prefix("Uploading");
+        let execution_arn = self
+            .start_job(bucket, &key, &file, &language, &args, &progress)
+            .await?;
+        progress.finish();
+        print_success(format!("Started job {}", name_from_arn(&execution_arn)));
+
+        let mut processed = ProcessedFile {
+            file,
+            key,
+            bucket: bucket.to_string(),
+            language,
+            name: name_from_arn(&execution_arn).to_string(),
+            execution_arn,
+            status: ExecutionStatus::Running.as_str().to_string(),
+            error: None,
+            cause: None,
+            transcript: None,
+            report: None,
+        };
+
+        if args.wait {
+            if let Err(err) = self.wait_for_completion(&mut processed).await {
+                // Scripts get the failed execution as well as the exit code
+                if matches!(err.downcast_ref(), Some(CommandError::JobFailed(_))) {
+                    print_output(&processed)?;
                 }
+                return Err(err);
             }
+
+            let transcript = self.fetch_transcript(bucket, &processed.key).await?;
+            processed.transcript =
+                Some(save_or_show("Transcript", transcript, args.transcript_output).await?);
+            let report = self.fetch_report(bucket, &processed.key).await?;
+            processed.report = Some(save_or_show("Report", report, args.report_output).await?);
         }
 
-        bail!("No execution found for file: {}", file_key)
+        print_output(&processed)
     }
 
-    pub(crate) async fn process_file(&self, args: ProcessArgs) -> Result<()> {
-        let ProcessArgs {
-            bucket,
+    /// Processes a file like `process --wait`, but on a progress row of its own so several can
+    /// run at once. The transcript and report are saved to their paths in `args` once the job
+    /// succeeds. A job that ran and didn't succeed is returned with its status, not as an error.
+    pub(crate) async fn process_file_on_row(
+        &self,
+        bucket: &str,
+        args: &ProcessArgs,
+        row: &ProgressBar,
+    ) -> Result<ProcessedFile> {
+        let file = args.file.clone().context("No file given")?;
+        let key = object_key(&file, &args.keys).await?;
+        let language = args
+            .language
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+
+        row.set_style(upload_style()?);
+        let execution_arn = self
+            .start_job(bucket, &key, &file, &language, args, row)
+            .await?;
+        row.set_style(spinner_style()?);
+        row.enable_steady_tick(Duration::from_millis(SPINNER_INTERVAL));
+
+        let mut steps = self.execution_progress(&execution_arn).await.ok();
+        let execution = self
+            .poll_execution(&execution_arn, row, steps.as_mut())
+            .await?;
+        let status = execution
+            .status()
+            .cloned()
+            .unwrap_or(ExecutionStatus::Failed);
+
+        let mut processed = ProcessedFile {
             file,
+            key,
+            bucket: bucket.to_string(),
             language,
-            transcript_output,
-            report_output,
-            ..
-        } = args;
+            name: name_from_arn(&execution_arn).to_string(),
+            execution_arn,
+            status: status.as_str().to_string(),
+            error: execution.error().map(String::from),
+            cause: execution.cause().map(String::from),
+            transcript: None,
+            report: None,
+        };
+        if status != ExecutionStatus::Succeeded {
+            row.finish_with_message(get_status_style(&status).to_string());
+            return Ok(processed);
+        }
 
-        if !file.exists() {
-            bail!("File does not exist: {:?}", file);
+        row.set_message("saving results");
+        let transcript = self.fetch_transcript(bucket, &processed.key).await?;
+        processed.transcript =
+            Some(save_document("transcript", transcript, &args.transcript_output).await?);
+        let report = self.fetch_report(bucket, &processed.key).await?;
+        processed.report = Some(save_document("report", report, &args.report_output).await?);
+        row.finish_with_message(get_status_style(&status).to_string());
+
+        Ok(processed)
+    }
+
+    /// Uploads and starts every recording named by `--batch`, a bounded number at a time, with a
+    /// progress row for each. With `--wait`, follows every run to the end and prints a summary.
+    pub(crate) async fn process_batch(&self, bucket: &str, args: ProcessArgs) -> Result<()> {
+        let files = find_recordings(&args.batch)?;
+        let language = args
+            .language
+            .clone()
+            .unwrap_or_else(|| DEFAULT_LANGUAGE.to_string());
+
+        print_header("Batch Job");
+        print_divider();
+        print_table_row("Files:", files.len().to_string());
+        print_table_row("Bucket:", bucket);
+        print_table_row("Language:", &language);
+        print_table_row("Concurrency:", args.concurrency.to_string());
+        print_divider();
+
+        let mut jobs = Vec::new();
+        let mut files_by_key: BTreeMap<String, PathBuf> = BTreeMap::new();
+        for file in files {
+            let key = object_key(&file, &args.keys).await?;
+            if let Some(other) = files_by_key.insert(key.clone(), file.clone()) {
+                bail!(
+                    "{:?} and {:?} would both be uploaded to {}; use --key-scheme date or hash to \
+                     keep them apart",
+                    other,
+                    file,
+                    key
+                );
+            }
+            jobs.push((key, file));
         }
+        let width = jobs
+            .iter()
+            .map(|(key, _)| key.len())
+            .max()
+            .unwrap_or_default();
 
-        let key = file
-            .file_name()
-            .ok_or_else(|| anyhow::anyhow!("Invalid filename"))?
-            .to_string_lossy()
-            .to_string();
+        let rows = MultiProgress::new();
+        let jobs = jobs
+            .into_iter()
+            .map(|(key, file)| {
+                let row = rows.add(ProgressBar::new_spinner().with_style(spinner_style()?));
+                row.set_prefix(format!("{:<width$}", key, width = width));
+                row.set_message("queued");
+                Ok((key, file, row))
+            })
+            .collect::<Result<Vec<_>>>()?;
 
-        print_header("Processing Job");
+        let started: Vec<_> = stream::iter(jobs)
+            .map(|(key, file, row)| {
+                let language = &language;
+                let args = &args;
+                async move {
+                    row.set_style(upload_style()?);
+                    let started = self
+                        .start_job(bucket, &key, &file, language, args, &row)
+                        .await;
+
+                    row.set_style(spinner_style()?);
+                    row.enable_steady_tick(Duration::from_millis(SPINNER_INTERVAL));
+                    match &started {
+                        Ok(execution_arn) if args.wait => {
+                            row.set_message(name_from_arn(execution_arn).to_string())
+                        }
+                        Ok(execution_arn) => row.finish_with_message(format!(
+                            "{} {}",
+                            style("started").green(),
+                            name_from_arn(execution_arn)
+                        )),
+                        Err(_) => row.finish_with_message(style("failed").red().to_string()),
+                    }
+                    anyhow::Ok((key, file, row, started))
+                }
+            })
+            .buffer_unordered(args.concurrency.get())
+            .try_collect()
+            .await?;
+
+        let total = started.len();
+        if !args.wait {
+            let mut failed = 0;
+            let mut results = Vec::new();
+            for (key, file, _, started) in started {
+                if let Err(err) = &started {
+                    print_error(format!("{}: {:#}", key, err));
+                    failed += 1;
+                }
+                results.push(BatchResult::started(file, key, started));
+            }
+            results.sort_by(|a, b| a.key.cmp(&b.key));
+            print_output(&results)?;
+
+            if failed > 0 {
+                bail!("{} of {} files could not be started", failed, total);
+            }
+            print_success(format!("Started {} executions", total));
+            return Ok(());
+        }
+
+        let mut results: Vec<(String, PathBuf, Result<DescribeExecutionOutput>)> =
+            future::join_all(
+                started
+                    .into_iter()
+                    .map(|(key, file, row, started)| async move {
+                        let execution = match started {
+                            Ok(execution_arn) => {
+                                self.poll_execution(&execution_arn, &row, None).await
+                            }
+                            Err(err) => Err(err),
+                        };
+                        match &execution {
+                            Ok(execution) => row.finish_with_message(
+                                get_status_style(
+                                    execution.status().unwrap_or(&ExecutionStatus::Failed),
+                                )
+                                .to_string(),
+                            ),
+                            Err(_) => row.finish_with_message(style("failed").red().to_string()),
+                        }
+                        (key, file, execution)
+                    }),
+            )
+            .await;
+        results.sort_by(|(a, _, _), (b, _, _)| a.cmp(b));
+
+        print_header("Batch Summary");
         print_divider();
-        print_table_row("File:", &key);
-        print_table_row("Bucket:", &bucket);
-        print_table_row("Language:", language.as_deref().unwrap_or(DEFAULT_LANGUAGE));
+        let mut failed = 0;
+        for (key, _, execution) in &results {
+            let summary = match execution {
+                Ok(execution) => {
+                    let status = execution.status().unwrap_or(&ExecutionStatus::Failed);
+                    if status != &ExecutionStatus::Succeeded {
+                        failed += 1;
+                    }
+                    match execution.error() {
+                        Some(error) => {
+                            format!("{} {}", get_status_style(status), style(error).dim())
+                        }
+                        None => get_status_style(status).to_string(),
+                    }
+                }
+                Err(err) => {
+                    failed += 1;
+                    format!(
+                        "{} {}",
+                        style("FAILED").red().bold(),
+                        style(format!("{:#}", err)).dim()
+                    )
+                }
+            };
+            print_aligned_row(key, summary, width);
+        }
+        print_divider();
+        print_table_row("Succeeded:", (total - failed).to_string());
+        print_table_row("Failed:", failed.to_string());
         print_divider();
 
-        self.s3_client
-            .put_object()
-            .bucket(&bucket)
-            .key(&key)
-            .body(fs::read(&file).await?.into())
-            .send()
-            .await
-            .context("Failed to upload file")?;
+        print_output(
+            &results
+                .into_iter()
+                .map(|(key, file, execution)| BatchResult::finished(file, key, execution))
+                .collect::<Vec<_>>(),
+        )?;
+
+        if failed > 0 {
+            bail!(CommandError::JobFailed(format!(
+                "{} of {} jobs failed",
+                failed, total
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Uploads an audio file and its documents, then starts a run on it and returns its ARN.
+    async fn start_job(
+        &self,
+        bucket: &str,
+        key: &str,
+        file: &Path,
+        language: &str,
+        args: &ProcessArgs,
+        progress: &ProgressBar,
+    ) -> Result<String> {
+        upload_file(&self.s3_client, bucket, key, file, progress).await?;
+
+        let entity_aliases_key = match &args.entity_aliases {
+            Some(path) => Some(
+                self.upload_json_document::<BTreeMap<String, Vec<String>>>(
+                    bucket,
+                    key,
+                    path,
+                    "entity-aliases.json",
+                    "entity aliases",
+                )
+                .await?,
+            ),
+            None => None,
+        };
+
+        let report_config_key = match &args.report_config {
+            Some(path) => Some(
+                self.upload_json_document::<serde_json::Map<String, serde_json::Value>>(
+                    bucket,
+                    key,
+                    path,
+                    "report-config.json",
+                    "report config",
+                )
+                .await?,
+            ),
+            None => None,
+        };
 
         let execution = self
             .sfn_client
             .start_execution()
-            .state_machine_arn(STATE_MACHINE_ARN)
+            .state_machine_arn(self.state_machine_arn()?)
+            .name(execution_name(bucket, key))
             .input(serde_json::to_string(&serde_json::json!({
                 "bucket": bucket,
                 "key": key,
-                "languageCode": language.unwrap_or_else(|| DEFAULT_LANGUAGE.to_string())
+                "languageCode": language,
+                "options": ExecutionOptions {
+                    sentiment_aggregation: args.sentiment_aggregation,
+                    entity_aliases_key,
+                    report_config_key,
+                },
             }))?)
             .send()
             .await
             .context("Failed to start execution")?;
 
-        let execution_arn = execution.execution_arn().context("Missing execution ARN")?;
+        let execution_arn = execution
+            .execution_arn()
+            .context("Missing execution ARN")?
+            .to_string();
+        self.index_execution(bucket, key, &execution_arn).await;
+        Ok(execution_arn)
+    }
 
-        if args.wait {
-            self.wait_for_completion(execution_arn).await?;
+    /// Validates a local JSON document as `T` and uploads it next to the audio file, returning its key.
+    async fn upload_json_document<T: DeserializeOwned>(
+        &self,
+        bucket: &str,
+        key: &str,
+        path: &PathBuf,
+        suffix: &str,
+        description: &str,
+    ) -> Result<String> {
+        let content = fs::read(path)
+            .await
+            .with_context(|| format!("Failed to read {}: {:?}", description, path))?;
 
-            self.get_transcript(&bucket, &key, transcript_output)
-                .await?;
-            self.get_report(&bucket, &key, report_output).await?;
-        }
+        serde_json::from_slice::<T>(&content)
+            .with_context(|| format!("Invalid {}: {:?}", description, path))?;
 
-        Ok(())
-    }
+        let document_key = format!("{}-{}", key, suffix);
+        self.s3_client
+            .put_object()
+            .bucket(bucket)
+            .key(&document_key)
+            .body(content.into())
+            .send()
+            .await
+            .with_context(|| format!("Failed to upload {}", description))?;
 
-    async fn wait_for_completion(&self, execution_arn: &str) -> Result<()> {
-        let progress_style = ProgressStyle::default_spinner()
-            .tick_chars(SPINNER_CHARS)
-            .template(&format!(
-                "{{spinner:.blue}} {{prefix:.bold.dim}} {{msg}} [{{elapsed_precise}}]{}",
-                " ".repeat(DIVIDER_WIDTH - 50)
-            ))
-            .context("Failed to create progress style")?;
+        Ok(document_key)
+    }
 
-        let progress = ProgressBar::new_spinner().with_style(progress_style);
+    /// Follows a job's execution to the end with a spinner, recording how it ended in
+    /// `processed`, and fails unless it succeeded.
+    async fn wait_for_completion(&self, processed: &mut ProcessedFile) -> Result<()> {
+        let execution_arn = processed.execution_arn.clone();
+        let progress = ProgressBar::new_spinner().with_style(spinner_style()?);
 
         progress.enable_steady_tick(Duration::from_millis(SPINNER_INTERVAL));
         progress.set_prefix("Processing");
         progress.set_message("initializing...");
 
+        let mut steps = match self.execution_progress(&execution_arn).await {
+            Ok(steps) => Some(steps),
+            Err(err) => {
+                debug!("Showing the execution without its steps: {:#}", err);
+                None
+            }
+        };
+
+        let execution = self
+            .poll_execution(&execution_arn, &progress, steps.as_mut())
+            .await?;
+        processed.status = execution
+            .status()
+            .map_or(UNKNOWN_STATUS, ExecutionStatus::as_str)
+            .to_string();
+        processed.error = execution.error().map(String::from);
+        processed.cause = execution.cause().map(String::from);
+
+        match execution.status() {
+            Some(ExecutionStatus::Succeeded) => {
+                progress.finish_with_message(format!("{}", style("complete").green().bold()));
+                print_divider();
+                if let Some(steps) = &steps {
+                    steps.print_phases();
+                }
+            }
+            Some(ExecutionStatus::Failed) => {
+                progress.finish_with_message(format!("{}", style("failed").red().bold()));
+                print_divider();
+                if let Some(steps) = &steps {
+                    steps.print_phases();
+                    if let Some(failure) = steps.failure() {
+                        bail!(CommandError::JobFailed(format!(
+                            "Execution failed in {}: {}: {}",
+                            failure.state, failure.error, failure.cause
+                        )));
+                    }
+                }
+                bail!(CommandError::JobFailed(String::from("Execution failed")));
+            }
+            status => {
+                progress.finish_with_message(status_style(status).to_string());
+                print_divider();
+                bail!(CommandError::JobFailed(format!(
+                    "Execution ended with status {}",
+                    status.map_or(UNKNOWN_STATUS, ExecutionStatus::as_str)
+                )))
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Describes an execution every few seconds until it stops running. Meanwhile it shows the
+    /// current state when following the steps, or the ARN otherwise.
+    async fn poll_execution(
+        &self,
+        execution_arn: &str,
+        progress: &ProgressBar,
+        mut steps: Option<&mut ExecutionProgress>,
+    ) -> Result<DescribeExecutionOutput> {
         loop {
             let execution = self
                 .sfn_client
@@ -215,123 +984,324 @@ impl Client {
                 .await
                 .context("Failed to get execution")?;
 
-            match execution
-                .status()
-                .expect("execution description should have a status")
-            {
-                ExecutionStatus::Running => {
-                    progress
-                        .set_message(truncate_arn(execution.execution_arn().unwrap_or_default()));
-                    sleep(Duration::from_secs(POLLING_INTERVAL)).await;
-                }
-                ExecutionStatus::Succeeded => {
-                    progress.finish_with_message(format!("{}", style("complete").green().bold()));
-                    print_divider();
-                    break;
-                }
-                ExecutionStatus::Failed => {
-                    progress.finish_with_message(format!("{}", style("failed").red().bold()));
-                    print_divider();
-                    bail!("Execution failed");
+            // Read the history even when the execution has stopped, for how it ended
+            match steps.as_deref_mut() {
+                Some(steps) => {
+                    let events = self
+                        .history_since(execution_arn, steps.last_event_id())
+                        .await?;
+                    steps.apply(&events);
+                    progress.set_message(steps.message());
                 }
-                status => bail!("Unexpected status: {:?}", status),
+                None => progress
+                    .set_message(truncate_arn(execution.execution_arn().unwrap_or_default())),
             }
+
+            if has_stopped(&execution) {
+                return Ok(execution);
+            }
+
+            sleep(Duration::from_secs(POLLING_INTERVAL)).await;
         }
+    }
 
-        Ok(())
+    /// Starts following an execution's steps, which needs the state machine's definition.
+    async fn execution_progress(&self, execution_arn: &str) -> Result<ExecutionProgress> {
+        let state_machine = self
+            .sfn_client
+            .describe_state_machine_for_execution()
+            .execution_arn(execution_arn)
+            .send()
+            .await
+            .context("Failed to get state machine definition")?;
+
+        Ok(ExecutionProgress::new(
+            state_machine.definition().unwrap_or_default(),
+        ))
+    }
+
+    /// The events of an execution after the one with ID `after`, oldest first. The history is
+    /// read newest first, so only the pages with new events are fetched.
+    async fn history_since(&self, execution_arn: &str, after: i64) -> Result<Vec<HistoryEvent>> {
+        let mut events = Vec::new();
+        let mut next_token = None;
+
+        loop {
+            let page = self
+                .sfn_client
+                .get_execution_history()
+                .execution_arn(execution_arn)
+                .reverse_order(true)
+                .include_execution_data(false)
+                .set_next_token(next_token)
+                .send()
+                .await
+                .context("Failed to get execution history")?;
+
+            let page_events = page.events().unwrap_or_default();
+            let new_events = page_events.iter().take_while(|event| event.id() > after);
+            let count = events.len();
+            events.extend(new_events.cloned());
+
+            next_token = page.next_token().map(String::from);
+            if events.len() - count < page_events.len() || next_token.is_none() {
+                break;
+            }
+        }
+
+        events.reverse();
+        Ok(events)
     }
 
     async fn get_object(&self, bucket: &str, key: &str) -> Result<bytes::Bytes> {
-        Ok(self
+        let object = match self
             .s3_client
             .get_object()
             .bucket(bucket)
             .key(key)
             .send()
-            .await?
-            .body
-            .collect()
-            .await?
-            .into_bytes())
+            .await
+        {
+            Ok(object) => object,
+            Err(SdkError::ServiceError(err)) if err.err().is_no_such_key() => {
+                bail!(CommandError::NotFound(format!(
+                    "No such object: s3://{}/{}",
+                    bucket, key
+                )))
+            }
+            Err(err) => {
+                return Err(err).with_context(|| format!("Failed to get s3://{}/{}", bucket, key))
+            }
+        };
+
+        Ok(object.body.collect().await?.into_bytes())
     }
 
-    pub(crate) async fn get_transcript(
-        &self,
-        bucket: &str,
-        key: &str,
-        output: Option<PathBuf>,
-    ) -> Result<()> {
-        let file_path = PathBuf::from(key);
-        let filename = file_path
-            .file_name()
-            .ok_or_else(|| anyhow::anyhow!("Invalid key format"))?;
-
-        let transcript_key = format!("{}-transcript.json", filename.to_string_lossy());
+    /// The text of a job's transcript.
+    pub(crate) async fn fetch_transcript(&self, bucket: &str, key: &str) -> Result<String> {
+        let transcript_key = format!("{}-transcript.json", key);
         let content = self.get_object(bucket, &transcript_key).await?;
 
         let transcript_json = serde_json::from_slice::<serde_json::Value>(&content)?;
 
-        let transcript = transcript_json
+        transcript_json
             .get("results")
             .and_then(|r| r.get("transcripts"))
             .and_then(|t| t.get(0))
             .and_then(|t| t.get("transcript"))
             .and_then(|t| t.as_str())
-            .ok_or_else(|| anyhow::anyhow!("Invalid transcript format"))?;
+            .map(String::from)
+            .ok_or_else(|| anyhow::anyhow!("Invalid transcript format"))
+    }
 
-        match output {
-            Some(path) => {
-                fs::write(&path, transcript).await?;
-                print_success(format!("Transcript saved to {:?}", path));
-            }
-            None => {
-                print_header("Transcript");
+    /// The Markdown of a job's report.
+    pub(crate) async fn fetch_report(&self, bucket: &str, key: &str) -> Result<String> {
+        let report_key = format!("{}-report.md", key);
+        let content = self.get_object(bucket, &report_key).await?;
+
+        Ok(String::from_utf8(content.to_vec())?
+            .trim_matches('"')
+            .replace("\\n", "\n")
+            .replace("\\\"", "\""))
+    }
+}
+
+/// A transcript or report: where it was saved, or its text when it wasn't.
+#[derive(Serialize)]
+pub(crate) struct Document {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    text: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<PathBuf>,
+}
+
+/// Saves a document when given a path, or otherwise shows it under a title. With JSON or YAML
+/// output the text is left for the caller to print.
+pub(crate) async fn save_or_show(
+    title: &str,
+    content: String,
+    output: Option<PathBuf>,
+) -> Result<Document> {
+    match output {
+        Some(path) => {
+            fs::write(&path, content)
+                .await
+                .with_context(|| format!("Failed to save {}: {:?}", title.to_lowercase(), path))?;
+            print_success(format!("{} saved to {:?}", title, path));
+            Ok(Document {
+                text: None,
+                path: Some(path),
+            })
+        }
+        None => {
+            if !is_structured() {
+                print_header(title);
                 print_divider();
-                println!("{}", transcript);
+                println!("{}", content);
                 print_divider();
             }
+            Ok(Document {
+                text: Some(content),
+                path: None,
+            })
         }
+    }
+}
 
-        Ok(())
+/// Saves a document to a path, when given one, without printing anything.
+async fn save_document(
+    description: &str,
+    content: String,
+    output: &Option<PathBuf>,
+) -> Result<Document> {
+    match output {
+        Some(path) => {
+            fs::write(path, content)
+                .await
+                .with_context(|| format!("Failed to save {}: {:?}", description, path))?;
+            Ok(Document {
+                text: None,
+                path: Some(path.clone()),
+            })
+        }
+        None => Ok(Document {
+            text: Some(content),
+            path: None,
+        }),
     }
+}
 
-    pub(crate) async fn get_report(
-        &self,
-        bucket: &str,
-        key: &str,
-        output: Option<PathBuf>,
-    ) -> Result<()> {
-        let file_path = PathBuf::from(key);
-        let filename = file_path
-            .file_name()
-            .ok_or_else(|| anyhow::anyhow!("Invalid key format"))?;
-
-        let transcript_key = format!("{}-report.md", filename.to_string_lossy());
-        let content = self.get_object(bucket, &transcript_key).await?;
+/// What `process` reports for a file it started a job for.
+#[derive(Serialize)]
+pub(crate) struct ProcessedFile {
+    file: PathBuf,
+    key: String,
+    bucket: String,
+    language: String,
+    name: String,
+    execution_arn: String,
+    status: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    error: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    cause: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    transcript: Option<Document>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    report: Option<Document>,
+}
 
-        let report = String::from_utf8(content.to_vec())?
-            .trim_matches('"')
-            .replace("\\n", "\n")
-            .replace("\\\"", "\"");
+impl ProcessedFile {
+    pub(crate) fn status(&self) -> &str {
+        &self.status
+    }
 
-        match output {
-            Some(path) => {
-                fs::write(&path, report).await?;
-                print_success(format!("Report saved to {:?}", path));
-            }
-            None => {
-                print_header("Report");
-                print_divider();
-                println!("{}", report);
-                print_divider();
-            }
+    pub(crate) fn succeeded(&self) -> bool {
+        self.status == ExecutionStatus::Succeeded.as_str()
+    }
+}
+
+/// What `process --batch` reports for each file.
+#[derive(Serialize)]
+struct BatchResult {
+    file: PathBuf,
+    key: String,
+    execution_arn: Option<String>,
+    status: String,
+    error: Option<String>,
+}
+
+impl BatchResult {
+    /// A file whose job was started and left running, or that failed to start.
+    fn started(file: PathBuf, key: String, started: Result<String>) -> Self {
+        match started {
+            Ok(execution_arn) => Self {
+                file,
+                key,
+                execution_arn: Some(execution_arn),
+                status: ExecutionStatus::Running.as_str().to_string(),
+                error: None,
+            },
+            Err(err) => Self::failed(file, key, err),
         }
+    }
 
-        Ok(())
+    /// A file whose job was followed to the end.
+    fn finished(file: PathBuf, key: String, execution: Result<DescribeExecutionOutput>) -> Self {
+        match execution {
+            Ok(execution) => Self {
+                file,
+                key,
+                execution_arn: execution.execution_arn().map(String::from),
+                status: execution
+                    .status()
+                    .unwrap_or(&ExecutionStatus::Failed)
+                    .as_str()
+                    .to_string(),
+                error: execution.error().map(String::from),
+            },
+            Err(err) => Self::failed(file, key, err),
+        }
+    }
+
+    fn failed(file: PathBuf, key: String, err: anyhow::Error) -> Self {
+        Self {
+            file,
+            key,
+            execution_arn: None,
+            status: ExecutionStatus::Failed.as_str().to_string(),
+            error: Some(format!("{:#}", err)),
+        }
+    }
+}
+
+fn spinner_style() -> Result<ProgressStyle> {
+    ProgressStyle::default_spinner()
+        .tick_chars(SPINNER_CHARS)
+        .template(&format!(
+            "{{spinner:.blue}} {{prefix:.bold.dim}} {{msg}} [{{elapsed_precise}}]{}",
+            " ".repeat(DIVIDER_WIDTH - 50)
+        ))
+        .context("Failed to create progress style")
+}
+
+fn print_execution(title: &str, job: &Job, execution: &DescribeExecutionOutput) {
+    print_header(title);
+    print_divider();
+    print_table_row("File:", &job.key);
+    print_table_row("Bucket:", &job.bucket);
+    print_table_row("Status:", status_style(execution.status()).to_string());
+    print_table_row(
+        "Execution:",
+        truncate_arn(execution.execution_arn().unwrap_or_default()),
+    );
+    print_divider();
+}
+
+/// Whether an execution has stopped running. A status added to Step Functions after this SDK,
+/// such as PENDING_REDRIVE, or a missing one only counts once the execution has a stop date.
+fn has_stopped(execution: &DescribeExecutionOutput) -> bool {
+    match execution.status() {
+        Some(ExecutionStatus::Running) => false,
+        Some(
+            ExecutionStatus::Succeeded
+            | ExecutionStatus::Failed
+            | ExecutionStatus::TimedOut
+            | ExecutionStatus::Aborted,
+        ) => true,
+        _ => execution.stop_date().is_some(),
+    }
+}
+
+/// A status styled for display, which may be missing from an execution description.
+pub(crate) fn status_style(status: Option<&ExecutionStatus>) -> StyledObject<String> {
+    match status {
+        Some(status) => get_status_style(status),
+        None => style(UNKNOWN_STATUS.to_string()).dim().bold(),
     }
 }
 
-fn get_status_style(status: &ExecutionStatus) -> StyledObject<String> {
+pub(crate) fn get_status_style(status: &ExecutionStatus) -> StyledObject<String> {
     match status {
         ExecutionStatus::Running => style(status.as_str().to_string()).yellow().bold(),
         ExecutionStatus::Succeeded => style(status.as_str().to_string()).green().bold(),
@@ -339,3 +1309,55 @@ fn get_status_style(status: &ExecutionStatus) -> StyledObject<String> {
         _ => style(status.as_str().to_string()).dim().bold(),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_sfn::primitives::DateTime;
+
+    fn execution(status: Option<ExecutionStatus>, stopped: bool) -> DescribeExecutionOutput {
+        DescribeExecutionOutput::builder()
+            .set_status(status)
+            .set_stop_date(stopped.then(|| DateTime::from_secs(1_717_243_200)))
+            .build()
+    }
+
+    #[test]
+    fn known_statuses_tell_whether_an_execution_stopped() {
+        assert!(!has_stopped(&execution(
+            Some(ExecutionStatus::Running),
+            false
+        )));
+        for status in [
+            ExecutionStatus::Succeeded,
+            ExecutionStatus::Failed,
+            ExecutionStatus::TimedOut,
+            ExecutionStatus::Aborted,
+        ] {
+            assert!(has_stopped(&execution(Some(status), false)));
+        }
+    }
+
+    #[test]
+    fn unknown_and_missing_statuses_stop_with_a_stop_date() {
+        let pending = ExecutionStatus::from("PENDING_REDRIVE");
+        assert!(!has_stopped(&execution(Some(pending.clone()), false)));
+        assert!(has_stopped(&execution(Some(pending), true)));
+        assert!(!has_stopped(&execution(None, false)));
+        assert!(has_stopped(&execution(None, true)));
+    }
+
+    #[test]
+    fn missing_statuses_are_shown_as_unknown() {
+        assert_eq!(
+            status_style(None).force_styling(false).to_string(),
+            "UNKNOWN"
+        );
+        assert_eq!(
+            status_style(Some(&ExecutionStatus::from("PENDING_REDRIVE")))
+                .force_styling(false)
+                .to_string(),
+            "PENDING_REDRIVE"
+        );
+    }
+}
@@ -0,0 +1,580 @@
+use crate::{
+    args::ConfigCommands,
+    display::{print_divider, print_header, print_success, print_table_row, print_warning},
+    output::{is_structured, print_output, CommandError},
+};
+use anyhow::{bail, Context, Result};
+use clap::ValueEnum;
+use console::style;
+use serde::{Deserialize, Serialize};
+use std::{collections::BTreeMap, env, fs, path::PathBuf};
+
+const CONFIG_PATH_VAR: &str = "DISTILLER_CONFIG";
+const ENVIRONMENT_VAR: &str = "DISTILLER_ENV";
+
+/// A setting that can come from the config file, the environment or a flag.
+#[derive(Clone, Copy, PartialEq, Eq, ValueEnum)]
+pub(crate) enum ConfigKey {
+    /// The ARN of the deployed AudioProcessingPipeline state machine.
+    StateMachineArn,
+    /// The S3 bucket audio files are uploaded to.
+    Bucket,
+    /// The Transcribe language code audio is transcribed in.
+    Language,
+    /// The AWS region.
+    Region,
+    /// The AWS profile.
+    Profile,
+}
+
+impl ConfigKey {
+    fn label(self) -> &'static str {
+        match self {
+            ConfigKey::StateMachineArn => "State Machine:",
+            ConfigKey::Bucket => "Bucket:",
+            ConfigKey::Language => "Language:",
+            ConfigKey::Region => "Region:",
+            ConfigKey::Profile => "Profile:",
+        }
+    }
+
+    fn env_var(self) -> &'static str {
+        match self {
+            ConfigKey::StateMachineArn => "DISTILLER_STATE_MACHINE_ARN",
+            ConfigKey::Bucket => "DISTILLER_BUCKET",
+            ConfigKey::Language => "DISTILLER_LANGUAGE",
+            ConfigKey::Region => "DISTILLER_REGION",
+            ConfigKey::Profile => "DISTILLER_PROFILE",
+        }
+    }
+
+    /// The variable read before `DISTILLER_`-prefixed ones, still honored below them.
+    fn deprecated_env_var(self) -> Option<&'static str> {
+        match self {
+            ConfigKey::StateMachineArn => Some("STATE_MACHINE_ARN"),
+            _ => None,
+        }
+    }
+
+    /// The name used on the command line, such as `state-machine-arn`.
+    fn name(self) -> String {
+        self.to_possible_value()
+            .map(|value| value.get_name().to_string())
+            .unwrap_or_default()
+    }
+}
+
+/// The settings for one deployment of the pipeline. Unset fields fall through to the next layer.
+#[derive(Clone, Debug, Default, Deserialize, Serialize)]
+pub(crate) struct Settings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) state_machine_arn: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) bucket: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) language: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) region: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub(crate) profile: Option<String>,
+}
+
+impl Settings {
+    fn get(&self, key: ConfigKey) -> Option<&String> {
+        match key {
+            ConfigKey::StateMachineArn => self.state_machine_arn.as_ref(),
+            ConfigKey::Bucket => self.bucket.as_ref(),
+            ConfigKey::Language => self.language.as_ref(),
+            ConfigKey::Region => self.region.as_ref(),
+            ConfigKey::Profile => self.profile.as_ref(),
+        }
+    }
+
+    fn get_mut(&mut self, key: ConfigKey) -> &mut Option<String> {
+        match key {
+            ConfigKey::StateMachineArn => &mut self.state_machine_arn,
+            ConfigKey::Bucket => &mut self.bucket,
+            ConfigKey::Language => &mut self.language,
+            ConfigKey::Region => &mut self.region,
+            ConfigKey::Profile => &mut self.profile,
+        }
+    }
+
+    fn from_env(lookup: &impl Fn(&str) -> Option<String>) -> Self {
+        Self::from_vars(|key| Some(key.env_var()), lookup)
+    }
+
+    fn from_deprecated_env(lookup: &impl Fn(&str) -> Option<String>) -> Self {
+        Self::from_vars(ConfigKey::deprecated_env_var, lookup)
+    }
+
+    /// Reads each key's variable, if it has one, with `lookup`.
+    fn from_vars(
+        var: impl Fn(ConfigKey) -> Option<&'static str>,
+        lookup: &impl Fn(&str) -> Option<String>,
+    ) -> Self {
+        let mut settings = Self::default();
+        for &key in ConfigKey::value_variants() {
+            *settings.get_mut(key) = var(key).and_then(lookup).filter(|v| !v.is_empty());
+        }
+        settings
+    }
+}
+
+/// The contents of `~/.config/distiller/config.toml`. Top-level settings apply to every
+/// environment, and each `[environments.<name>]` table overrides them for that deployment.
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub(crate) struct ConfigFile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    default_environment: Option<String>,
+    #[serde(flatten)]
+    defaults: Settings,
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    environments: BTreeMap<String, Settings>,
+}
+
+impl ConfigFile {
+    /// `$DISTILLER_CONFIG`, or `config.toml` under `$XDG_CONFIG_HOME/distiller` or `~/.config/distiller`.
+    pub(crate) fn path() -> Result<PathBuf> {
+        if let Some(path) = env::var_os(CONFIG_PATH_VAR) {
+            return Ok(PathBuf::from(path));
+        }
+
+        let config_home = env::var_os("XDG_CONFIG_HOME")
+            .filter(|dir| !dir.is_empty())
+            .map(PathBuf::from)
+            .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+            .context("Could not find the home directory")?;
+
+        Ok(config_home.join("distiller").join("config.toml"))
+    }
+
+    /// Reads the config file, which is empty when it doesn't exist yet.
+    pub(crate) fn load(path: &PathBuf) -> Result<Self> {
+        match fs::read_to_string(path) {
+            Ok(content) => {
+                toml::from_str(&content).with_context(|| format!("Invalid config file: {:?}", path))
+            }
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("Failed to read config file: {:?}", path)),
+        }
+    }
+
+    fn save(&self, path: &PathBuf) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("Failed to create config directory: {:?}", parent))?;
+        }
+
+        fs::write(path, toml::to_string_pretty(self)?)
+            .with_context(|| format!("Failed to write config file: {:?}", path))
+    }
+
+    /// The `--env` flag, `$DISTILLER_ENV`, or the file's `default_environment`.
+    fn select_environment(
+        &self,
+        flag: Option<String>,
+        lookup: &impl Fn(&str) -> Option<String>,
+    ) -> Option<String> {
+        flag.or_else(|| lookup(ENVIRONMENT_VAR).filter(|v| !v.is_empty()))
+            .or_else(|| self.default_environment.clone())
+    }
+
+    fn settings(&self, environment: Option<&str>) -> Result<&Settings> {
+        match environment {
+            Some(name) => self
+                .environments
+                .get(name)
+                .with_context(|| format!("Unknown environment: {}", name)),
+            None => Ok(&self.defaults),
+        }
+    }
+
+    fn settings_mut(&mut self, environment: Option<&str>) -> &mut Settings {
+        match environment {
+            Some(name) => self.environments.entry(name.to_string()).or_default(),
+            None => &mut self.defaults,
+        }
+    }
+}
+
+/// Where the CLI keeps state between runs: `$XDG_STATE_HOME/distiller`, or `~/.local/state/distiller`.
+pub(crate) fn state_dir() -> Result<PathBuf> {
+    let state_home = env::var_os("XDG_STATE_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map(PathBuf::from)
+        .or_else(|| {
+            env::var_os("HOME").map(|home| PathBuf::from(home).join(".local").join("state"))
+        })
+        .context("Could not find the home directory")?;
+
+    Ok(state_home.join("distiller"))
+}
+
+fn env_var(name: &str) -> Option<String> {
+    env::var(name).ok()
+}
+
+/// Where a setting in effect came from.
+enum Source {
+    Flag,
+    EnvVar,
+    DeprecatedEnvVar,
+    Environment(String),
+    File,
+}
+
+impl Source {
+    fn describe(&self, key: ConfigKey) -> String {
+        match self {
+            Source::Flag => String::from("flag"),
+            Source::EnvVar => key.env_var().to_string(),
+            Source::DeprecatedEnvVar => format!(
+                "{}, deprecated for {}",
+                key.deprecated_env_var().unwrap_or_default(),
+                key.env_var()
+            ),
+            Source::Environment(name) => format!("environment {}", name),
+            Source::File => String::from("config file"),
+        }
+    }
+}
+
+/// The settings in effect.
+pub(crate) struct Config {
+    pub(crate) path: PathBuf,
+    pub(crate) environment: Option<String>,
+    pub(crate) settings: Settings,
+    layers: Vec<(Settings, Source)>,
+}
+
+impl Config {
+    /// Layers flags over environment variables over the selected environment over the top-level
+    /// settings of the config file. Deprecated variables sit just below their replacements.
+    pub(crate) fn resolve(flags: Settings, environment: Option<String>) -> Result<Self> {
+        let path = ConfigFile::path()?;
+        let file = ConfigFile::load(&path)?;
+        Self::from_layers(path, file, flags, environment, &env_var)
+    }
+
+    /// Resolves the settings with environment variables read by `lookup`.
+    fn from_layers(
+        path: PathBuf,
+        file: ConfigFile,
+        flags: Settings,
+        environment: Option<String>,
+        lookup: &impl Fn(&str) -> Option<String>,
+    ) -> Result<Self> {
+        let environment = file.select_environment(environment, lookup);
+
+        let mut layers = vec![
+            (flags, Source::Flag),
+            (Settings::from_env(lookup), Source::EnvVar),
+            (
+                Settings::from_deprecated_env(lookup),
+                Source::DeprecatedEnvVar,
+            ),
+        ];
+        if let Some(name) = &environment {
+            layers.push((
+                file.settings(Some(name))?.clone(),
+                Source::Environment(name.clone()),
+            ));
+        }
+        layers.push((file.defaults, Source::File));
+
+        let mut settings = Settings::default();
+        for &key in ConfigKey::value_variants() {
+            let found = layers
+                .iter()
+                .find_map(|(layer, source)| layer.get(key).map(|value| (value, source)));
+            if let Some((_, Source::DeprecatedEnvVar)) = found {
+                print_warning(format!(
+                    "{} is deprecated; set {} instead",
+                    key.deprecated_env_var().unwrap_or_default(),
+                    key.env_var()
+                ));
+            }
+            *settings.get_mut(key) = found.map(|(value, _)| value.clone());
+        }
+
+        Ok(Self {
+            path,
+            environment,
+            settings,
+            layers,
+        })
+    }
+
+    /// Describes the layer a setting in effect came from.
+    fn source(&self, key: ConfigKey) -> Option<String> {
+        self.layers
+            .iter()
+            .find(|(layer, _)| layer.get(key).is_some())
+            .map(|(_, source)| source.describe(key))
+    }
+
+    pub(crate) fn bucket(&self) -> Result<&str> {
+        self.settings.bucket.as_deref().context(
+            "No bucket given; pass --bucket or set one with `distiller config set bucket <name>`",
+        )
+    }
+}
+
+/// Reads and writes the config file. Settings are read from and saved to the selected
+/// environment, or the top level when there is none.
+pub(crate) fn run_config_command(
+    command: ConfigCommands,
+    flags: Settings,
+    environment: Option<String>,
+) -> Result<()> {
+    let path = ConfigFile::path()?;
+    let mut file = ConfigFile::load(&path)?;
+    let environment = file.select_environment(environment, &env_var);
+    let section = match &environment {
+        Some(name) => format!("environment {} in {:?}", name, path),
+        None => format!("{:?}", path),
+    };
+
+    match command {
+        ConfigCommands::Show => {
+            let config = Config::resolve(flags, environment)?;
+            if is_structured() {
+                let settings: serde_json::Map<_, _> = ConfigKey::value_variants()
+                    .iter()
+                    .map(|&key| {
+                        let setting = serde_json::json!({
+                            "value": config.settings.get(key),
+                            "source": config.source(key),
+                        });
+                        (key.name(), setting)
+                    })
+                    .collect();
+                return print_output(&serde_json::json!({
+                    "config_file": config.path,
+                    "environment": config.environment,
+                    "settings": settings,
+                }));
+            }
+            print_header("Configuration");
+            print_divider();
+            print_table_row("Config File:", config.path.display().to_string());
+            print_table_row("Environment:", config.environment.as_deref().unwrap_or("—"));
+            for &key in ConfigKey::value_variants() {
+                let value = match (config.settings.get(key), config.source(key)) {
+                    (Some(value), Some(source)) => {
+                        format!("{} {}", value, style(format!("({})", source)).dim())
+                    }
+                    _ => style("not set").dim().to_string(),
+                };
+                print_table_row(key.label(), value);
+            }
+            print_divider();
+        }
+        ConfigCommands::Path if is_structured() => {
+            print_output(&serde_json::json!({ "path": path }))?
+        }
+        ConfigCommands::Path => println!("{}", path.display()),
+        ConfigCommands::Get { key } => match file.settings(environment.as_deref())?.get(key) {
+            Some(value) if is_structured() => print_output(&serde_json::json!({
+                "key": key.name(),
+                "value": value,
+            }))?,
+            Some(value) => println!("{}", value),
+            None => bail!(CommandError::NotFound(format!(
+                "{} is not set in {}",
+                key.name(),
+                section
+            ))),
+        },
+        ConfigCommands::Set { key, value } => {
+            *file.settings_mut(environment.as_deref()).get_mut(key) = Some(value);
+            file.save(&path)?;
+            print_success(format!("Saved {} to {}", key.name(), section));
+        }
+        ConfigCommands::Unset { key } => {
+            *file.settings_mut(environment.as_deref()).get_mut(key) = None;
+            file.save(&path)?;
+            print_success(format!("Removed {} from {}", key.name(), section));
+        }
+        ConfigCommands::Use { environment } => {
+            if !file.environments.contains_key(&environment) {
+                bail!("Unknown environment: {}", environment);
+            }
+            file.default_environment = Some(environment.clone());
+            file.save(&path)?;
+            print_success(format!("Using environment {} by default", environment));
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const FLAG: &str = "arn:aws:states:us-east-1:123456789012:stateMachine:Flag";
+    const ENV_VAR: &str = "arn:aws:states:us-east-1:123456789012:stateMachine:EnvVar";
+    const DEPRECATED: &str = "arn:aws:states:us-east-1:123456789012:stateMachine:Deprecated";
+    const ENVIRONMENT: &str = "arn:aws:states:eu-west-1:123456789012:stateMachine:Prod";
+    const FILE: &str = "arn:aws:states:us-east-1:123456789012:stateMachine:File";
+
+    fn resolve(
+        file: &str,
+        flags: Settings,
+        environment: Option<&str>,
+        vars: &[(&str, &str)],
+    ) -> Result<Config> {
+        let vars: BTreeMap<String, String> = vars
+            .iter()
+            .map(|(name, value)| (name.to_string(), value.to_string()))
+            .collect();
+        Config::from_layers(
+            PathBuf::from("config.toml"),
+            toml::from_str(file).unwrap(),
+            flags,
+            environment.map(String::from),
+            &|name| vars.get(name).cloned(),
+        )
+    }
+
+    /// Sets the state machine ARN in each of the given layers, with `prod` selected.
+    fn resolve_layers(layers: &[&str]) -> Config {
+        let set = |layer: &str, value: &str| {
+            if layers.contains(&layer) {
+                format!("state_machine_arn = {:?}\n", value)
+            } else {
+                String::new()
+            }
+        };
+        let file = format!(
+            "default_environment = \"prod\"\n{}\n[environments.prod]\n{}",
+            set("file", FILE),
+            set("environment", ENVIRONMENT)
+        );
+        let flags = Settings {
+            state_machine_arn: layers.contains(&"flag").then(|| FLAG.to_string()),
+            ..Default::default()
+        };
+        let mut vars = Vec::new();
+        if layers.contains(&"env_var") {
+            vars.push(("DISTILLER_STATE_MACHINE_ARN", ENV_VAR));
+        }
+        if layers.contains(&"deprecated") {
+            vars.push(("STATE_MACHINE_ARN", DEPRECATED));
+        }
+
+        resolve(&file, flags, None, &vars).unwrap()
+    }
+
+    #[test]
+    fn layers_take_precedence_in_order() {
+        let all = ["flag", "env_var", "deprecated", "environment", "file"];
+        let cases = [
+            (&all[..], FLAG, "flag"),
+            (&all[1..], ENV_VAR, "DISTILLER_STATE_MACHINE_ARN"),
+            (
+                &all[2..],
+                DEPRECATED,
+                "STATE_MACHINE_ARN, deprecated for DISTILLER_STATE_MACHINE_ARN",
+            ),
+            (&all[3..], ENVIRONMENT, "environment prod"),
+            (&all[4..], FILE, "config file"),
+        ];
+
+        for (layers, value, source) in cases {
+            let config = resolve_layers(layers);
+            assert_eq!(
+                config.settings.state_machine_arn.as_deref(),
+                Some(value),
+                "with {:?}",
+                layers
+            );
+            assert_eq!(
+                config.source(ConfigKey::StateMachineArn).as_deref(),
+                Some(source),
+                "with {:?}",
+                layers
+            );
+        }
+    }
+
+    #[test]
+    fn lower_layers_fill_in_when_higher_ones_are_missing() {
+        let config = resolve_layers(&["flag", "file"]);
+        assert_eq!(config.settings.state_machine_arn.as_deref(), Some(FLAG));
+
+        let config = resolve_layers(&["deprecated", "file"]);
+        assert_eq!(
+            config.settings.state_machine_arn.as_deref(),
+            Some(DEPRECATED)
+        );
+
+        let config = resolve_layers(&[]);
+        assert_eq!(config.settings.state_machine_arn, None);
+        assert_eq!(config.source(ConfigKey::StateMachineArn), None);
+    }
+
+    #[test]
+    fn each_key_resolves_on_its_own() {
+        let file = "bucket = \"file-bucket\"\nregion = \"us-east-1\"\nlanguage = \"en-US\"\n";
+        let flags = Settings {
+            language: Some("es-US".to_string()),
+            ..Default::default()
+        };
+        let config = resolve(file, flags, None, &[("DISTILLER_BUCKET", "env-bucket")]).unwrap();
+
+        for (key, value, source) in [
+            (ConfigKey::Bucket, "env-bucket", "DISTILLER_BUCKET"),
+            (ConfigKey::Region, "us-east-1", "config file"),
+            (ConfigKey::Language, "es-US", "flag"),
+        ] {
+            assert_eq!(config.settings.get(key).map(String::as_str), Some(value));
+            assert_eq!(config.source(key).as_deref(), Some(source));
+        }
+        assert_eq!(config.settings.profile, None);
+    }
+
+    #[test]
+    fn empty_variables_are_unset() {
+        let config = resolve(
+            "bucket = \"file-bucket\"\n",
+            Settings::default(),
+            None,
+            &[("DISTILLER_BUCKET", ""), ("DISTILLER_ENV", "")],
+        )
+        .unwrap();
+
+        assert_eq!(config.settings.bucket.as_deref(), Some("file-bucket"));
+        assert_eq!(config.environment, None);
+    }
+
+    #[test]
+    fn environments_are_selected_by_flag_then_variable_then_file() {
+        let file = "default_environment = \"dev\"\n\
+                    [environments.dev]\nbucket = \"dev-bucket\"\n\
+                    [environments.staging]\nbucket = \"staging-bucket\"\n\
+                    [environments.prod]\nbucket = \"prod-bucket\"\n";
+        let vars = [("DISTILLER_ENV", "staging")];
+
+        for (flag, vars, expected) in [
+            (Some("prod"), &vars[..], "prod"),
+            (None, &vars[..], "staging"),
+            (None, &[][..], "dev"),
+        ] {
+            let config = resolve(file, Settings::default(), flag, vars).unwrap();
+            assert_eq!(config.environment.as_deref(), Some(expected));
+            assert_eq!(config.settings.bucket, Some(format!("{}-bucket", expected)));
+        }
+    }
+
+    #[test]
+    fn unknown_environments_are_an_error() {
+        let error = resolve("", Settings::default(), Some("prod"), &[])
+            .err()
+            .unwrap();
+
+        assert_eq!(error.to_string(), "Unknown environment: prod");
+    }
+}
@@ -1,44 +1,134 @@
-use console::style;
+use crate::output::is_structured;
+use anyhow::{bail, Result};
+use console::{measure_text_width, pad_str, style, user_attended, user_attended_stderr, Alignment};
+use std::{
+    fmt::Display,
+    io::{self, Write},
+};
 
 const SUCCESS_PREFIX: &str = "✅";
 const ERROR_PREFIX: &str = "❌";
+const WARNING_PREFIX: &str = "⚠️";
 const DIVIDER_WIDTH: usize = 60;
 const DIVIDER_CHAR: &str = "─";
 const TABLE_VERTICAL: &str = "│";
 const LABEL_WIDTH: usize = 15;
 
+/// Prints a line for people: to stdout, or to stderr when stdout carries JSON or YAML.
+fn print_line(line: impl Display) {
+    if is_structured() {
+        eprintln!("{}", line);
+    } else {
+        println!("{}", line);
+    }
+}
+
 pub(crate) fn print_divider() {
-    println!("{}", style(DIVIDER_CHAR.repeat(DIVIDER_WIDTH)).dim());
+    print_line(style(DIVIDER_CHAR.repeat(DIVIDER_WIDTH)).dim());
 }
 
 pub(crate) fn print_header(text: &str) {
-    println!("\n{}", style(text).bold());
+    print_line(format!("\n{}", style(text).bold()));
 }
 
 pub(crate) fn print_table_row(label: &str, value: impl AsRef<str>) {
-    println!(
+    print_aligned_row(label, value, LABEL_WIDTH);
+}
+
+/// A table row whose labels are padded to `width`, for tables with longer labels such as file names.
+pub(crate) fn print_aligned_row(label: &str, value: impl AsRef<str>, width: usize) {
+    print_line(format!(
         "{} {:<width$} {}",
         style(TABLE_VERTICAL).dim(),
         style(label).bold(),
         value.as_ref(),
-        width = LABEL_WIDTH
+        width = width
+    ));
+}
+
+/// Prints rows under bold headers, each column as wide as its widest cell. Cells may be styled.
+pub(crate) fn print_grid(headers: &[&str], rows: &[Vec<String>]) {
+    let widths: Vec<usize> = headers
+        .iter()
+        .enumerate()
+        .map(|(i, header)| {
+            rows.iter()
+                .map(|row| measure_text_width(&row[i]))
+                .chain([header.len()])
+                .max()
+                .unwrap_or_default()
+        })
+        .collect();
+
+    let print_row = |cells: Vec<String>| {
+        let cells: Vec<_> = cells
+            .iter()
+            .zip(&widths)
+            .map(|(cell, &width)| pad_str(cell, width, Alignment::Left, None).to_string())
+            .collect();
+        print_line(format!(
+            "{} {}",
+            style(TABLE_VERTICAL).dim(),
+            cells.join("  ").trim_end()
+        ));
+    };
+
+    print_row(
+        headers
+            .iter()
+            .map(|header| style(header).bold().to_string())
+            .collect(),
     );
+    for row in rows {
+        print_row(row.clone());
+    }
 }
 
 pub(crate) fn print_error(message: impl AsRef<str>) {
-    println!(
+    print_line(format!(
         "\n{} {}",
         style(ERROR_PREFIX).red(),
         style(message.as_ref()).red().bold()
-    );
+    ));
+}
+
+pub(crate) fn print_warning(message: impl AsRef<str>) {
+    print_line(format!(
+        "\n{} {}",
+        style(WARNING_PREFIX).yellow(),
+        style(message.as_ref()).yellow().bold()
+    ));
 }
 
 pub(crate) fn print_success(message: impl AsRef<str>) {
-    println!(
+    print_line(format!(
         "\n{} {}",
         style(SUCCESS_PREFIX).green(),
         style(message.as_ref()).green().bold()
-    );
+    ));
+}
+
+/// A side note, such as why nothing was done.
+pub(crate) fn print_note(message: impl AsRef<str>) {
+    print_line(style(message.as_ref()).dim());
+}
+
+/// Asks a yes-or-no question, taking anything but yes as no.
+pub(crate) fn confirm(question: &str) -> Result<bool> {
+    let prompt = format!("\n{} {} ", style(question).bold(), style("[y/N]").dim());
+    if is_structured() && user_attended_stderr() {
+        eprint!("{}", prompt);
+        io::stderr().flush()?;
+    } else if !is_structured() && user_attended() {
+        print!("{}", prompt);
+        io::stdout().flush()?;
+    } else {
+        bail!("Not running in a terminal; pass --yes to confirm");
+    }
+
+    let mut answer = String::new();
+    io::stdin().read_line(&mut answer)?;
+    Ok(matches!(answer.trim().to_lowercase().as_str(), "y" | "yes"))
 }
 
 pub(crate) fn truncate_arn(arn: &str) -> String {
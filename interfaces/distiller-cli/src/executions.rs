@@ -0,0 +1,189 @@
+use aws_sdk_sfn::types::ExecutionStatus;
+use serde::{Deserialize, Serialize};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Keeps names readable while leaving room for the hash and timestamp within the 80 character limit.
+const MAX_SLUG_LENGTH: usize = 40;
+const HASH_LENGTH: usize = 16;
+
+/// Narrows the executions searched when looking up a file.
+#[derive(Default)]
+pub(crate) struct ExecutionFilter {
+    pub(crate) status: Option<ExecutionStatus>,
+    /// Only executions started this long ago or later.
+    pub(crate) since: Option<Duration>,
+}
+
+impl ExecutionFilter {
+    /// The earliest start time, in seconds since the epoch, that passes the filter.
+    pub(crate) fn cutoff(&self) -> Option<i64> {
+        let since = self.since?;
+        let cutoff = SystemTime::now().checked_sub(since).unwrap_or(UNIX_EPOCH);
+        Some(
+            cutoff
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64,
+        )
+    }
+}
+
+/// The document next to an audio file naming its most recent execution, so the run can be found
+/// with one read instead of searching the execution history.
+#[derive(Deserialize, Serialize)]
+pub(crate) struct ExecutionIndex {
+    pub(crate) execution_arn: String,
+}
+
+/// The object key of a file's execution index, stored next to it like its transcript.
+pub(crate) fn execution_index_key(key: &str) -> String {
+    format!("{}-execution.json", key)
+}
+
+/// FNV-1a, which unlike the standard library's hasher gives the same result on every Rust release.
+pub(crate) fn fnv1a(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xcbf29ce484222325, |hash, &byte| {
+        (hash ^ byte as u64).wrapping_mul(0x100000001b3)
+    })
+}
+
+/// The start of every execution name for an object: a readable slug of the key and a hash of the
+/// bucket and key, so runs on a file can be found from its name alone.
+fn execution_name_prefix(bucket: &str, key: &str) -> String {
+    let slug: String = key
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '-' })
+        .take(MAX_SLUG_LENGTH)
+        .collect();
+    let slug = slug.trim_matches('-');
+
+    format!(
+        "{}-{:0width$x}-",
+        if slug.is_empty() { "audio" } else { slug },
+        fnv1a(format!("{}/{}", bucket, key).as_bytes()),
+        width = HASH_LENGTH
+    )
+}
+
+/// A name for a new run on an object, unique to the millisecond it started.
+pub(crate) fn execution_name(bucket: &str, key: &str) -> String {
+    let started = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_millis();
+
+    format!("{}{}", execution_name_prefix(bucket, key), started)
+}
+
+/// Whether an execution was named by [`execution_name`] for this object. The hash must match
+/// exactly, so an object key that merely looks like an execution name isn't taken for one.
+pub(crate) fn is_derived_name(name: &str, bucket: &str, key: &str) -> bool {
+    name.strip_prefix(&execution_name_prefix(bucket, key))
+        .is_some_and(|started| !started.is_empty() && started.chars().all(|c| c.is_ascii_digit()))
+}
+
+/// Whether a name has the shape [`execution_name`] gives, for some object. Older runs got random
+/// names, so the only way to tell which file they processed is to read their input.
+pub(crate) fn has_derived_shape(name: &str) -> bool {
+    let mut parts = name.rsplitn(3, '-');
+    let (Some(started), Some(hash), Some(_)) = (parts.next(), parts.next(), parts.next()) else {
+        return false;
+    };
+
+    !started.is_empty()
+        && started.chars().all(|c| c.is_ascii_digit())
+        && hash.len() == HASH_LENGTH
+        && hash
+            .chars()
+            .all(|c| c.is_ascii_digit() || matches!(c, 'a'..='f'))
+}
+
+/// The ARN of an execution of a state machine, from its name.
+pub(crate) fn execution_arn(state_machine_arn: &str, name: &str) -> String {
+    format!(
+        "{}:{}",
+        state_machine_arn.replacen(":stateMachine:", ":execution:", 1),
+        name
+    )
+}
+
+/// The name of an execution, which is the last part of its ARN.
+pub(crate) fn name_from_arn(execution_arn: &str) -> &str {
+    execution_arn.rsplit(':').next().unwrap_or(execution_arn)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fnv1a_matches_reference_values() {
+        assert_eq!(fnv1a(b""), 0xcbf29ce484222325);
+        assert_eq!(fnv1a(b"a"), 0xaf63dc4c8601ec8c);
+        assert_eq!(fnv1a(b"foobar"), 0x85944171f73967e8);
+    }
+
+    #[test]
+    fn execution_names_are_valid_and_derived_from_the_object() {
+        let name = execution_name("bucket", "team/2024/Weekly Sync.mp3");
+        let prefix = execution_name_prefix("bucket", "team/2024/Weekly Sync.mp3");
+
+        assert!(name.starts_with("team-2024-Weekly-Sync-mp3-"));
+        assert!(name.starts_with(&prefix));
+        assert!(name[prefix.len()..].chars().all(|c| c.is_ascii_digit()));
+        assert!(name.len() <= 80);
+        assert!(name.chars().all(|c| c.is_ascii_alphanumeric() || c == '-'));
+    }
+
+    #[test]
+    fn execution_names_stay_short_and_never_empty() {
+        let long = execution_name("bucket", &"a".repeat(200));
+        assert!(long.len() <= 80);
+        assert!(long.starts_with(&format!("{}-", "a".repeat(MAX_SLUG_LENGTH))));
+
+        assert!(execution_name("bucket", "日本語.m4a").starts_with("m4a-"));
+        assert!(execution_name("bucket", "???").starts_with("audio-"));
+    }
+
+    #[test]
+    fn execution_name_prefixes_differ_by_bucket_and_key() {
+        let prefix = execution_name_prefix("bucket", "meeting.mp3");
+        assert_eq!(prefix, execution_name_prefix("bucket", "meeting.mp3"));
+        assert_ne!(prefix, execution_name_prefix("other", "meeting.mp3"));
+        assert_ne!(prefix, execution_name_prefix("bucket", "meeting?mp3"));
+    }
+
+    #[test]
+    fn derived_names_match_only_their_own_object() {
+        let name = execution_name("bucket", "meeting.mp3");
+        assert!(is_derived_name(&name, "bucket", "meeting.mp3"));
+        assert!(has_derived_shape(&name));
+
+        assert!(!is_derived_name(&name, "other", "meeting.mp3"));
+        assert!(!is_derived_name(&name, "bucket", "meeting?mp3"));
+        assert!(!is_derived_name(
+            &execution_name_prefix("bucket", "meeting.mp3"),
+            "bucket",
+            "meeting.mp3"
+        ));
+    }
+
+    #[test]
+    fn keys_shaped_like_execution_names_are_not_derived() {
+        let key = "meeting-mp3-0123456789abcdef-1717243200000";
+        assert!(has_derived_shape(key));
+        assert!(!is_derived_name(key, "bucket", "meeting.mp3"));
+        assert!(!is_derived_name(key, "bucket", key));
+    }
+
+    #[test]
+    fn other_names_do_not_have_the_derived_shape() {
+        assert!(!has_derived_shape("meeting.mp3"));
+        assert!(!has_derived_shape("3f2a9c0d-1b7e-4a56-9c0d-1b7e4a563f2a"));
+        assert!(!has_derived_shape(
+            "meeting-mp3-0123456789ABCDEF-1717243200000"
+        ));
+        assert!(!has_derived_shape("meeting-mp3-0123456789abcdef-"));
+        assert!(!has_derived_shape("0123456789abcdef-1717243200000"));
+    }
+}
@@ -0,0 +1,272 @@
+use crate::{
+    args::JobSort,
+    client::{status_style, UNKNOWN_STATUS},
+    display::{print_divider, print_grid, print_header, print_note},
+    executions::name_from_arn,
+    output::{is_structured, print_output},
+};
+use anyhow::Result;
+use aws_sdk_sfn::{
+    operation::describe_execution::DescribeExecutionOutput,
+    primitives::{DateTime, DateTimeFormat},
+    types::ExecutionStatus,
+};
+use serde::{ser::SerializeStruct, Serialize, Serializer};
+use std::{
+    cmp::Reverse,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const HEADERS: [&str; 7] = [
+    "Key", "Bucket", "Language", "Status", "Started", "Stopped", "Duration",
+];
+
+/// A past or running execution of the pipeline and the audio file it processed.
+pub(crate) struct JobSummary {
+    pub(crate) execution_arn: String,
+    pub(crate) key: Option<String>,
+    pub(crate) bucket: Option<String>,
+    pub(crate) language: Option<String>,
+    /// Missing when Step Functions didn't report one.
+    pub(crate) status: Option<ExecutionStatus>,
+    pub(crate) started: Option<DateTime>,
+    pub(crate) stopped: Option<DateTime>,
+    pub(crate) error: Option<String>,
+    pub(crate) cause: Option<String>,
+}
+
+impl JobSummary {
+    /// Reads the audio file a run processed from its input.
+    pub(crate) fn from_execution(execution: &DescribeExecutionOutput) -> Self {
+        let input: serde_json::Value = execution
+            .input()
+            .and_then(|input| serde_json::from_str(input).ok())
+            .unwrap_or_default();
+        let field = |name: &str| input[name].as_str().map(String::from);
+
+        Self {
+            execution_arn: execution.execution_arn().unwrap_or_default().to_string(),
+            key: field("key"),
+            bucket: field("bucket"),
+            language: field("languageCode"),
+            status: execution.status().cloned(),
+            started: execution.start_date().cloned(),
+            stopped: execution.stop_date().cloned(),
+            error: execution.error().map(String::from),
+            cause: execution.cause().map(String::from),
+        }
+    }
+
+    fn status_name(&self) -> &str {
+        self.status
+            .as_ref()
+            .map_or(UNKNOWN_STATUS, ExecutionStatus::as_str)
+    }
+
+    /// How long the execution ran, or has been running so far.
+    fn duration(&self) -> Option<i64> {
+        let started = self.started?.secs();
+        let stopped = match self.stopped {
+            Some(stopped) => stopped.secs(),
+            None => SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap_or_default()
+                .as_secs() as i64,
+        };
+        Some(stopped - started)
+    }
+}
+
+impl Serialize for JobSummary {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut job = serializer.serialize_struct("JobSummary", 11)?;
+        job.serialize_field("name", name_from_arn(&self.execution_arn))?;
+        job.serialize_field("execution_arn", &self.execution_arn)?;
+        job.serialize_field("key", &self.key)?;
+        job.serialize_field("bucket", &self.bucket)?;
+        job.serialize_field("language", &self.language)?;
+        job.serialize_field("status", self.status_name())?;
+        job.serialize_field("started", &timestamp(self.started))?;
+        job.serialize_field("stopped", &timestamp(self.stopped))?;
+        job.serialize_field("duration_seconds", &self.duration())?;
+        job.serialize_field("error", &self.error)?;
+        job.serialize_field("cause", &self.cause)?;
+        job.end()
+    }
+}
+
+/// Such as `2024-06-01T10:00:00Z`.
+fn timestamp(time: Option<DateTime>) -> Option<String> {
+    time.and_then(|time| {
+        DateTime::from_secs(time.secs())
+            .fmt(DateTimeFormat::DateTime)
+            .ok()
+    })
+}
+
+/// Such as `2024-06-01 10:00:00`, in UTC.
+fn format_time(time: Option<DateTime>) -> String {
+    timestamp(time)
+        .map(|time| time.replace('T', " ").trim_end_matches('Z').to_string())
+        .unwrap_or_else(|| String::from("—"))
+}
+
+/// Such as `1h 02m 03s`, `4m 05s` or `12s`.
+pub(crate) fn format_duration(seconds: Option<i64>) -> String {
+    let Some(seconds) = seconds.filter(|seconds| *seconds >= 0) else {
+        return String::from("—");
+    };
+
+    match (seconds / 3600, seconds % 3600 / 60, seconds % 60) {
+        (0, 0, s) => format!("{}s", s),
+        (0, m, s) => format!("{}m {:02}s", m, s),
+        (h, m, s) => format!("{}h {:02}m {:02}s", h, m, s),
+    }
+}
+
+/// Sorts jobs newest or longest first for times and durations, and alphabetically for the rest.
+pub(crate) fn sort_jobs(jobs: &mut [JobSummary], sort: JobSort, reverse: bool) {
+    match sort {
+        JobSort::Started => jobs.sort_by_key(|job| Reverse(job.started.map(|t| t.secs()))),
+        JobSort::Stopped => jobs.sort_by_key(|job| Reverse(job.stopped.map(|t| t.secs()))),
+        JobSort::Duration => jobs.sort_by_key(|job| Reverse(job.duration())),
+        JobSort::Key => jobs.sort_by(|a, b| a.key.cmp(&b.key)),
+        JobSort::Status => jobs.sort_by(|a, b| a.status_name().cmp(b.status_name())),
+    }
+
+    if reverse {
+        jobs.reverse();
+    }
+}
+
+pub(crate) fn print_jobs(jobs: &[JobSummary]) -> Result<()> {
+    if is_structured() {
+        return print_output(&jobs);
+    }
+
+    let rows: Vec<Vec<String>> = jobs
+        .iter()
+        .map(|job| {
+            vec![
+                job.key.as_deref().unwrap_or("—").to_string(),
+                job.bucket.as_deref().unwrap_or("—").to_string(),
+                job.language.as_deref().unwrap_or("—").to_string(),
+                status_style(job.status.as_ref()).to_string(),
+                format_time(job.started),
+                format_time(job.stopped),
+                format_duration(job.duration()),
+            ]
+        })
+        .collect();
+
+    print_header("Jobs");
+    print_divider();
+    if rows.is_empty() {
+        print_note("No jobs found");
+    } else {
+        print_grid(&HEADERS, &rows);
+    }
+    print_divider();
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(key: &str, status: Option<ExecutionStatus>, started: i64, stopped: i64) -> JobSummary {
+        JobSummary {
+            execution_arn: format!(
+                "arn:aws:states:us-east-1:123456789012:execution:AudioProcessingPipeline:{}",
+                key
+            ),
+            key: Some(key.to_string()),
+            bucket: Some("meetings".to_string()),
+            language: None,
+            status,
+            started: Some(DateTime::from_secs(started)),
+            stopped: Some(DateTime::from_secs(stopped)),
+            error: None,
+            cause: None,
+        }
+    }
+
+    fn jobs() -> Vec<JobSummary> {
+        vec![
+            job("b.m4a", Some(ExecutionStatus::Succeeded), 2_000, 2_600),
+            job("c.m4a", None, 3_000, 3_100),
+            job("a.m4a", Some(ExecutionStatus::Failed), 1_000, 4_000),
+        ]
+    }
+
+    fn keys(jobs: &[JobSummary]) -> Vec<&str> {
+        jobs.iter().map(|job| job.key.as_deref().unwrap()).collect()
+    }
+
+    #[test]
+    fn sorts_times_and_durations_largest_first() {
+        for (name, sort, expected) in [
+            ("started", JobSort::Started, ["c.m4a", "b.m4a", "a.m4a"]),
+            ("stopped", JobSort::Stopped, ["a.m4a", "c.m4a", "b.m4a"]),
+            ("duration", JobSort::Duration, ["a.m4a", "b.m4a", "c.m4a"]),
+        ] {
+            let mut jobs = jobs();
+            sort_jobs(&mut jobs, sort, false);
+            assert_eq!(keys(&jobs), expected, "sorting by {}", name);
+        }
+    }
+
+    #[test]
+    fn sorts_keys_and_statuses_alphabetically() {
+        let mut by_key = jobs();
+        sort_jobs(&mut by_key, JobSort::Key, false);
+        assert_eq!(keys(&by_key), ["a.m4a", "b.m4a", "c.m4a"]);
+
+        // FAILED, SUCCEEDED, UNKNOWN
+        let mut by_status = jobs();
+        sort_jobs(&mut by_status, JobSort::Status, false);
+        assert_eq!(keys(&by_status), ["a.m4a", "b.m4a", "c.m4a"]);
+    }
+
+    #[test]
+    fn reverse_flips_the_order() {
+        let mut jobs = jobs();
+        sort_jobs(&mut jobs, JobSort::Started, true);
+        assert_eq!(keys(&jobs), ["a.m4a", "b.m4a", "c.m4a"]);
+    }
+
+    #[test]
+    fn jobs_without_times_sort_last() {
+        let mut jobs = jobs();
+        jobs[0].started = None;
+        sort_jobs(&mut jobs, JobSort::Started, false);
+        assert_eq!(keys(&jobs), ["c.m4a", "a.m4a", "b.m4a"]);
+    }
+
+    #[test]
+    fn formats_durations() {
+        assert_eq!(format_duration(Some(0)), "0s");
+        assert_eq!(format_duration(Some(12)), "12s");
+        assert_eq!(format_duration(Some(245)), "4m 05s");
+        assert_eq!(format_duration(Some(3600)), "1h 00m 00s");
+        assert_eq!(format_duration(Some(3723)), "1h 02m 03s");
+        assert_eq!(format_duration(Some(-5)), "—");
+        assert_eq!(format_duration(None), "—");
+    }
+
+    #[test]
+    fn a_missing_status_is_unknown() {
+        let execution = DescribeExecutionOutput::builder()
+            .input(r#"{"bucket": "meetings", "key": "standup.m4a"}"#)
+            .build();
+        let job = JobSummary::from_execution(&execution);
+
+        assert_eq!(job.status, None);
+        assert_eq!(job.key.as_deref(), Some("standup.m4a"));
+        assert_eq!(
+            serde_json::to_value(&job).unwrap()["status"],
+            UNKNOWN_STATUS
+        );
+    }
+}
@@ -0,0 +1,177 @@
+use crate::{
+    args::{KeyOptions, KeyScheme},
+    upload::modified_secs,
+};
+use anyhow::{Context, Result};
+use aws_sdk_s3::primitives::{DateTime, DateTimeFormat};
+use sha2::{Digest, Sha256};
+use std::path::Path;
+use tokio::{fs, io::AsyncReadExt};
+
+/// Enough of the SHA-256 digest that different recordings won't share a folder.
+const HASH_LENGTH: usize = 16;
+const READ_BUFFER_SIZE: usize = 1024 * 1024;
+
+/// The SHA-256 digest of a file in hex, read a buffer at a time so large recordings aren't
+/// loaded into memory.
+async fn content_hash(file: &Path) -> Result<String> {
+    let mut reader = fs::File::open(file)
+        .await
+        .with_context(|| format!("Failed to open file: {:?}", file))?;
+
+    let mut hasher = Sha256::new();
+    let mut buffer = vec![0; READ_BUFFER_SIZE];
+    loop {
+        let read = reader
+            .read(&mut buffer)
+            .await
+            .with_context(|| format!("Failed to read file: {:?}", file))?;
+        if read == 0 {
+            break;
+        }
+        hasher.update(&buffer[..read]);
+    }
+
+    Ok(hasher
+        .finalize()
+        .iter()
+        .map(|byte| format!("{:02x}", byte))
+        .collect())
+}
+
+/// The UTC date a file was last modified, such as `2024-06-01`, which for a recording is
+/// usually the day it was made.
+async fn modified_date(file: &Path) -> Result<String> {
+    let metadata = fs::metadata(file)
+        .await
+        .with_context(|| format!("Failed to read file: {:?}", file))?;
+
+    let timestamp = DateTime::from_secs(modified_secs(&metadata) as i64)
+        .fmt(DateTimeFormat::DateTime)
+        .context("Failed to format modification date")?;
+
+    Ok(timestamp[..10].to_string())
+}
+
+/// A prefix as a folder: without a leading slash or empty segments, and with a trailing slash
+/// unless it's empty.
+fn normalize_prefix(prefix: Option<&str>) -> String {
+    prefix
+        .unwrap_or_default()
+        .split('/')
+        .filter(|segment| !segment.is_empty())
+        .map(|segment| format!("{}/", segment))
+        .collect()
+}
+
+/// The object key an audio file is uploaded to, by its prefix and key scheme.
+pub(crate) async fn object_key(file: &Path, options: &KeyOptions) -> Result<String> {
+    let name = file
+        .file_name()
+        .context("Invalid filename")?
+        .to_string_lossy()
+        .to_string();
+
+    let key = match options.key_scheme {
+        KeyScheme::Name => name,
+        KeyScheme::Date => format!("{}/{}", modified_date(file).await?, name),
+        KeyScheme::Hash => format!("{}/{}", &content_hash(file).await?[..HASH_LENGTH], name),
+    };
+
+    Ok(format!(
+        "{}{}",
+        normalize_prefix(options.prefix.as_deref()),
+        key
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::{
+        path::PathBuf,
+        time::{Duration, UNIX_EPOCH},
+    };
+    use tempfile::TempDir;
+
+    /// A recording last modified at 2024-06-01 10:00:00 UTC.
+    fn recording(dir: &TempDir) -> PathBuf {
+        let path = dir.path().join("standup.m4a");
+        std::fs::write(&path, "standup audio").unwrap();
+        std::fs::File::options()
+            .write(true)
+            .open(&path)
+            .unwrap()
+            .set_modified(UNIX_EPOCH + Duration::from_secs(1_717_236_000))
+            .unwrap();
+        path
+    }
+
+    fn options(prefix: Option<&str>, key_scheme: KeyScheme) -> KeyOptions {
+        KeyOptions {
+            prefix: prefix.map(String::from),
+            key_scheme,
+        }
+    }
+
+    #[test]
+    fn prefixes_become_folders() {
+        for (prefix, expected) in [
+            (None, ""),
+            (Some(""), ""),
+            (Some("/"), ""),
+            (Some("meetings"), "meetings/"),
+            (Some("/meetings"), "meetings/"),
+            (Some("meetings/"), "meetings/"),
+            (Some("//team//2024///"), "team/2024/"),
+        ] {
+            assert_eq!(normalize_prefix(prefix), expected, "for {:?}", prefix);
+        }
+    }
+
+    #[tokio::test]
+    async fn keys_follow_the_scheme() {
+        let dir = TempDir::new().unwrap();
+        let file = recording(&dir);
+
+        for (key_scheme, expected) in [
+            (KeyScheme::Name, "standup.m4a"),
+            (KeyScheme::Date, "2024-06-01/standup.m4a"),
+            (KeyScheme::Hash, "b8f67f8605fb394d/standup.m4a"),
+        ] {
+            assert_eq!(
+                object_key(&file, &options(None, key_scheme)).await.unwrap(),
+                expected
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn keys_go_under_the_prefix() {
+        let dir = TempDir::new().unwrap();
+        let file = recording(&dir);
+
+        assert_eq!(
+            object_key(&file, &options(Some("/team/"), KeyScheme::Date))
+                .await
+                .unwrap(),
+            "team/2024-06-01/standup.m4a"
+        );
+    }
+
+    #[tokio::test]
+    async fn missing_files_have_no_content_keys() {
+        let dir = TempDir::new().unwrap();
+        let file = dir.path().join("missing.m4a");
+
+        assert_eq!(
+            object_key(&file, &options(None, KeyScheme::Name))
+                .await
+                .unwrap(),
+            "missing.m4a"
+        );
+        assert!(object_key(&file, &options(None, KeyScheme::Hash))
+            .await
+            .is_err());
+    }
+}
@@ -1,22 +1,47 @@
 use anyhow::Result;
 use clap::Parser;
+use std::process::ExitCode;
 
 mod args;
+mod batch;
 mod client;
+mod config;
 mod display;
+mod executions;
+mod jobs;
+mod keys;
+mod output;
+mod progress;
+mod upload;
+mod watch;
 
 use args::{Args, Commands, GetCommands};
-use client::Client;
+use client::{save_or_show, Client};
+use config::{run_config_command, Config, Settings};
+use display::print_error;
+use executions::ExecutionFilter;
+use jobs::{print_jobs, sort_jobs};
+use output::{exit_code, is_structured, print_output};
 
 #[tokio::main]
-async fn main() -> Result<()> {
+async fn main() -> ExitCode {
     let Args {
         profile,
         region,
+        environment,
+        state_machine_arn,
+        bucket,
         verbose,
+        output,
         command,
     } = Args::parse();
 
+    output::set_format(output);
+    if is_structured() {
+        // Messages for people go to stderr, so style them for it
+        console::set_colors_enabled(console::colors_enabled_stderr());
+    }
+
     if verbose > 0 {
         tracing_subscriber::fmt()
             .with_max_level(match verbose {
@@ -30,29 +55,101 @@ async fn main() -> Result<()> {
             .init();
     }
 
-    let client = Client::new(profile, region).await;
+    let flags = Settings {
+        state_machine_arn,
+        bucket,
+        region,
+        profile,
+        ..Default::default()
+    };
 
     let result = match command {
-        Commands::Get(cmd) => match cmd {
-            GetCommands::Buckets => client.list_buckets().await,
-            GetCommands::Status { bucket, key } => client.get_status(&bucket, &key).await,
-            GetCommands::Report {
-                bucket,
-                key,
-                output,
-            } => client.get_report(&bucket, &key, output).await,
-            GetCommands::Transcript {
-                bucket,
-                key,
-                output,
-            } => client.get_transcript(&bucket, &key, output).await,
-        },
-        Commands::Process(args) => client.process_file(args).await,
+        Commands::Config(cmd) => run_config_command(cmd, flags, environment),
+        command => run(command, flags, environment).await,
     };
 
-    if let Err(err) = result {
-        tracing::error!("{}", err);
+    match result {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(err) => {
+            print_error(format!("{:#}", err));
+            tracing::error!("{}", err);
+            exit_code(&err)
+        }
     }
+}
 
-    Ok(())
+async fn run(command: Commands, flags: Settings, environment: Option<String>) -> Result<()> {
+    let config = Config::resolve(flags, environment)?;
+    let client = Client::new(&config.settings).await;
+
+    match command {
+        Commands::Get(cmd) => match cmd {
+            GetCommands::Buckets => client.list_buckets().await,
+            GetCommands::Status { job, status, since } => {
+                let job = client.resolve_job(config.bucket()?, &job).await?;
+                let filter = ExecutionFilter {
+                    status: status.map(Into::into),
+                    since,
+                };
+                client.get_status(&job, filter).await
+            }
+            GetCommands::Jobs {
+                status,
+                since,
+                key_contains,
+                sort,
+                reverse,
+                limit,
+            } => {
+                let filter = ExecutionFilter {
+                    status: status.map(Into::into),
+                    since,
+                };
+                let mut jobs = client
+                    .list_jobs(&filter, key_contains.as_deref(), limit)
+                    .await?;
+                sort_jobs(&mut jobs, sort, reverse);
+                print_jobs(&jobs)
+            }
+            GetCommands::Report { job, save } => {
+                let job = client.resolve_job(config.bucket()?, &job).await?;
+                let report = client.fetch_report(&job.bucket, &job.key).await?;
+                print_output(&serde_json::json!({
+                    "key": job.key,
+                    "bucket": job.bucket,
+                    "report": save_or_show("Report", report, save).await?,
+                }))
+            }
+            GetCommands::Transcript { job, save } => {
+                let job = client.resolve_job(config.bucket()?, &job).await?;
+                let transcript = client.fetch_transcript(&job.bucket, &job.key).await?;
+                print_output(&serde_json::json!({
+                    "key": job.key,
+                    "bucket": job.bucket,
+                    "transcript": save_or_show("Transcript", transcript, save).await?,
+                }))
+            }
+        },
+        Commands::Process(mut args) => {
+            args.language = args.language.or(config.settings.language.clone());
+            if args.batch.is_empty() {
+                client.process_file(config.bucket()?, args).await
+            } else {
+                client.process_batch(config.bucket()?, args).await
+            }
+        }
+        Commands::Watch(mut args) => {
+            args.language = args.language.or(config.settings.language.clone());
+            watch::watch(&client, config.bucket()?, args).await
+        }
+        Commands::Cancel { job, yes } => {
+            let job = client.resolve_job(config.bucket()?, &job).await?;
+            client.cancel(&job, yes).await
+        }
+        Commands::Retry { job, yes } => {
+            let job = client.resolve_job(config.bucket()?, &job).await?;
+            client.retry(&job, yes).await
+        }
+        Commands::Config(_) => unreachable!("config commands don't need a client"),
+    }
 }
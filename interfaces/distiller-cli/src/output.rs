@@ -0,0 +1,139 @@
+use crate::args::OutputFormat;
+use anyhow::Result;
+use serde::Serialize;
+use std::{fmt, process::ExitCode, sync::OnceLock};
+
+static FORMAT: OnceLock<OutputFormat> = OnceLock::new();
+
+/// Errors scripts may want to tell apart, each with its own exit code. Anything else exits with 1,
+/// and invalid arguments with 2.
+#[derive(Debug)]
+pub(crate) enum CommandError {
+    /// A job ran and didn't succeed.
+    JobFailed(String),
+    /// A job, document or setting that was asked for doesn't exist.
+    NotFound(String),
+}
+
+impl fmt::Display for CommandError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::JobFailed(message) | Self::NotFound(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for CommandError {}
+
+/// The exit code for an error, by the first `CommandError` in its chain.
+pub(crate) fn exit_code(err: &anyhow::Error) -> ExitCode {
+    let code = match err
+        .chain()
+        .find_map(|err| err.downcast_ref::<CommandError>())
+    {
+        Some(CommandError::JobFailed(_)) => 3,
+        Some(CommandError::NotFound(_)) => 4,
+        None => 1,
+    };
+    ExitCode::from(code)
+}
+
+pub(crate) fn set_format(format: OutputFormat) {
+    FORMAT.get_or_init(|| format);
+}
+
+/// Whether stdout carries JSON or YAML, in which case messages and tables for people go to stderr.
+pub(crate) fn is_structured() -> bool {
+    FORMAT
+        .get()
+        .is_some_and(|format| *format != OutputFormat::Table)
+}
+
+/// Prints a command's result to stdout as JSON or YAML. Tables are printed by the commands.
+pub(crate) fn print_output(value: &impl Serialize) -> Result<()> {
+    match FORMAT.get().copied().unwrap_or_default() {
+        OutputFormat::Table => {}
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(value)?),
+        OutputFormat::Yaml => print!("---\n{}", serde_yaml::to_string(value)?),
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::{json, Value};
+
+    /// Writes a value as YAML and reads it back.
+    fn round_trip(value: Value) {
+        let yaml = serde_yaml::to_string(&value).unwrap();
+        let read: Value = serde_yaml::from_str(&yaml).unwrap();
+        assert_eq!(read, value, "{}", yaml);
+    }
+
+    #[test]
+    fn nested_arrays_of_objects_round_trip() {
+        round_trip(json!([
+            {"key": "a.mp3", "steps": [{"name": "Transcribe", "seconds": 12.5}, []]},
+            {"key": "b.mp3", "steps": [[1, 2], [{"nested": [true, null]}]]},
+        ]));
+    }
+
+    #[test]
+    fn multi_line_text_round_trips() {
+        round_trip(json!({
+            "report": "# Report\n\nSummary line\n",
+            "trailing": "text\n\n\n",
+            "leading": "\n  indented first line\nnext",
+            "no_newline": "one\ntwo",
+            "control": "tab\there\u{7}bell\r\nwindows",
+        }));
+    }
+
+    #[test]
+    fn keys_and_strings_yaml_would_misread_round_trip() {
+        let words = [
+            "yes",
+            "No",
+            "on",
+            "off",
+            "y",
+            "~",
+            "null",
+            "true",
+            "1a",
+            "1",
+            "0x1f",
+            "1e3",
+            ".inf",
+            "a b",
+            "a: b",
+            "- a",
+            "#a",
+            "",
+            " ",
+            "&a",
+            "*a",
+            "!a",
+            "%a",
+            "@a",
+            "`a",
+            "'a",
+            "\"a",
+            "2024-06-01",
+            "12:30",
+        ];
+        for word in words {
+            round_trip(json!({ word: word }));
+        }
+    }
+
+    #[test]
+    fn empty_collections_and_scalars_round_trip() {
+        round_trip(json!({"map": {}, "list": [], "nothing": null}));
+        round_trip(json!({}));
+        round_trip(json!([]));
+        round_trip(json!("text"));
+        round_trip(json!(null));
+        round_trip(json!({"big": u64::MAX, "small": i64::MIN, "float": 0.1}));
+    }
+}
@@ -0,0 +1,524 @@
+use crate::{
+    display::{print_aligned_row, print_divider, print_header},
+    jobs::format_duration,
+};
+use aws_sdk_sfn::types::{HistoryEvent, HistoryEventType};
+use std::{
+    collections::{HashMap, HashSet},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+/// Time spent in one top-level state, which may be entered many times, like the Transcribe
+/// polling loop.
+struct Phase {
+    name: String,
+    seconds: f64,
+    entries: usize,
+}
+
+/// Why an execution failed and in which state.
+pub(crate) struct Failure {
+    pub(crate) state: String,
+    pub(crate) error: String,
+    pub(crate) cause: String,
+}
+
+/// Follows an execution through its history: the top-level state it's in, the states running
+/// inside top-level Parallel branches, how many items each Map has finished, and how long each
+/// top-level state took.
+pub(crate) struct ExecutionProgress {
+    top_level: HashSet<String>,
+    branch_states: HashSet<String>,
+    last_event_id: i64,
+    /// When the last event seen happened.
+    last_event_time: f64,
+    /// The state each event happened in, by event ID. Events inherit the state of the event
+    /// before them, which is how failures deep inside a Map are traced back to their state.
+    event_states: HashMap<i64, String>,
+    /// The top-level state the execution is in and when it was entered.
+    current: Option<(String, f64)>,
+    /// Branch states running now, in the order they started.
+    active: Vec<String>,
+    /// Finished and total items of each Map, by name.
+    maps: HashMap<String, (i32, i32)>,
+    phases: Vec<Phase>,
+    failure: Option<Failure>,
+}
+
+fn state_names(states: &serde_json::Value) -> impl Iterator<Item = &String> {
+    states["States"]
+        .as_object()
+        .into_iter()
+        .flat_map(|states| states.keys())
+}
+
+fn timestamp(event: &HistoryEvent) -> f64 {
+    event.timestamp().map_or(0.0, |time| time.as_secs_f64())
+}
+
+fn now() -> f64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap_or_default()
+        .as_secs_f64()
+}
+
+/// Lambda failures carry a JSON cause with the stack trace; only its message is worth showing.
+fn cause_message(cause: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(cause)
+        .ok()
+        .and_then(|cause| cause["errorMessage"].as_str().map(String::from))
+        .unwrap_or_else(|| cause.to_string())
+}
+
+impl ExecutionProgress {
+    /// Reads the names of the top-level states and of the states directly in top-level
+    /// Parallel branches from the state machine's definition.
+    pub(crate) fn new(definition: &str) -> Self {
+        let definition: serde_json::Value = serde_json::from_str(definition).unwrap_or_default();
+
+        let top_level = state_names(&definition).cloned().collect();
+        let branch_states = definition["States"]
+            .as_object()
+            .into_iter()
+            .flat_map(|states| states.values())
+            .filter(|state| state["Type"] == "Parallel")
+            .flat_map(|state| state["Branches"].as_array().into_iter().flatten())
+            .flat_map(state_names)
+            .cloned()
+            .collect();
+
+        Self {
+            top_level,
+            branch_states,
+            last_event_id: 0,
+            last_event_time: 0.0,
+            event_states: HashMap::new(),
+            current: None,
+            active: Vec::new(),
+            maps: HashMap::new(),
+            phases: Vec::new(),
+            failure: None,
+        }
+    }
+
+    /// The ID of the last event seen, so only newer events need to be fetched.
+    pub(crate) fn last_event_id(&self) -> i64 {
+        self.last_event_id
+    }
+
+    /// Applies events, oldest first.
+    pub(crate) fn apply(&mut self, events: &[HistoryEvent]) {
+        for event in events {
+            self.apply_event(event);
+        }
+    }
+
+    fn apply_event(&mut self, event: &HistoryEvent) {
+        self.last_event_id = self.last_event_id.max(event.id());
+        self.last_event_time = self.last_event_time.max(timestamp(event));
+
+        let entered = event
+            .state_entered_event_details()
+            .and_then(|details| details.name());
+        let state = match entered {
+            Some(name) => Some(name.to_string()),
+            None => self.event_states.get(&event.previous_event_id()).cloned(),
+        };
+        if let Some(state) = &state {
+            self.event_states.insert(event.id(), state.clone());
+        }
+
+        if let Some(name) = entered {
+            if self.top_level.contains(name) {
+                self.current = Some((name.to_string(), timestamp(event)));
+            } else if self.branch_states.contains(name) {
+                self.active.push(name.to_string());
+            }
+        }
+
+        let exited = event
+            .state_exited_event_details()
+            .and_then(|details| details.name());
+        if let Some(name) = exited {
+            if self.top_level.contains(name) {
+                self.exit_phase(name, timestamp(event));
+            } else {
+                self.active.retain(|active| active != name);
+            }
+        }
+
+        if let (Some(details), Some(state)) = (event.map_state_started_event_details(), &state) {
+            self.maps.insert(state.clone(), (0, details.length()));
+        }
+
+        let iteration = event
+            .map_iteration_succeeded_event_details()
+            .or(event.map_iteration_failed_event_details())
+            .or(event.map_iteration_aborted_event_details());
+        if let Some(name) = iteration.and_then(|details| details.name()) {
+            if let Some((finished, _)) = self.maps.get_mut(name) {
+                *finished += 1;
+            }
+        }
+
+        // A task that failed and was then retried or caught has recovered, so its failure no
+        // longer explains how the execution ends
+        if let Some(failure) = &self.failure {
+            let recovered = match event.r#type() {
+                Some(
+                    HistoryEventType::TaskSucceeded | HistoryEventType::LambdaFunctionSucceeded,
+                ) => state.as_deref() == Some(failure.state.as_str()),
+                _ => entered.or(exited) == Some(failure.state.as_str()),
+            };
+            if recovered {
+                self.failure = None;
+            }
+        }
+
+        let failure = match event.r#type() {
+            Some(HistoryEventType::TaskFailed) => event
+                .task_failed_event_details()
+                .map(|d| (d.error(), d.cause())),
+            Some(HistoryEventType::TaskTimedOut) => event
+                .task_timed_out_event_details()
+                .map(|d| (d.error(), d.cause())),
+            Some(HistoryEventType::LambdaFunctionFailed) => event
+                .lambda_function_failed_event_details()
+                .map(|d| (d.error(), d.cause())),
+            Some(HistoryEventType::LambdaFunctionTimedOut) => event
+                .lambda_function_timed_out_event_details()
+                .map(|d| (d.error(), d.cause())),
+            // A failed task is more telling than the execution failing because of it
+            Some(HistoryEventType::ExecutionFailed) if self.failure.is_none() => event
+                .execution_failed_event_details()
+                .map(|d| (d.error(), d.cause())),
+            _ => None,
+        };
+        if let Some((error, cause)) = failure {
+            self.failure = Some(Failure {
+                state: state
+                    .or_else(|| self.current.as_ref().map(|(name, _)| name.clone()))
+                    .unwrap_or_else(|| String::from("unknown state")),
+                error: error.unwrap_or("Error").to_string(),
+                cause: cause.map(cause_message).unwrap_or_default(),
+            });
+        }
+    }
+
+    fn exit_phase(&mut self, name: &str, exited: f64) {
+        let entered = match self.current.take() {
+            Some((current, entered)) if current == name => entered,
+            other => {
+                self.current = other;
+                return;
+            }
+        };
+
+        let seconds = (exited - entered).max(0.0);
+        match self.phases.iter_mut().find(|phase| phase.name == name) {
+            Some(phase) => {
+                phase.seconds += seconds;
+                phase.entries += 1;
+            }
+            None => self.phases.push(Phase {
+                name: name.to_string(),
+                seconds,
+                entries: 1,
+            }),
+        }
+    }
+
+    /// The state the execution is in and for how long, then the branch states running inside
+    /// it with the progress of any Map, such as
+    /// `Analysis (1m 05s) · Process Chunks for Bedrock 7/12 · Process Chunks for Comprehend 12/12`.
+    pub(crate) fn message(&self) -> String {
+        let Some((name, entered)) = &self.current else {
+            // Between two top-level states
+            return self
+                .phases
+                .last()
+                .map_or_else(|| String::from("starting..."), |phase| phase.name.clone());
+        };
+
+        let mut parts = vec![format!(
+            "{} ({})",
+            name,
+            format_duration(Some((now() - entered) as i64))
+        )];
+        for state in &self.active {
+            parts.push(match self.maps.get(state) {
+                Some((finished, total)) => format!("{} {}/{}", state, finished, total),
+                None => state.clone(),
+            });
+        }
+        parts.join(" · ")
+    }
+
+    pub(crate) fn failure(&self) -> Option<&Failure> {
+        self.failure.as_ref()
+    }
+
+    /// Prints how long each top-level state took, with how often it was entered when it looped.
+    /// A state the execution stopped in, such as the one that failed, counts until the last event.
+    pub(crate) fn print_phases(&self) {
+        let mut phases: Vec<(&str, f64, usize)> = self
+            .phases
+            .iter()
+            .map(|phase| (phase.name.as_str(), phase.seconds, phase.entries))
+            .collect();
+        if let Some((name, entered)) = &self.current {
+            let seconds = (self.last_event_time - entered).max(0.0);
+            match phases.iter_mut().find(|(phase, _, _)| phase == name) {
+                Some(phase) => {
+                    phase.1 += seconds;
+                    phase.2 += 1;
+                }
+                None => phases.push((name, seconds, 1)),
+            }
+        }
+        if phases.is_empty() {
+            return;
+        }
+
+        let width = phases
+            .iter()
+            .map(|(name, _, _)| name.len())
+            .max()
+            .unwrap_or_default();
+
+        print_header("Phases");
+        print_divider();
+        for (name, seconds, entries) in phases {
+            let duration = format_duration(Some(seconds.round() as i64));
+            let value = match entries {
+                1 => duration,
+                entries => format!("{} ({}×)", duration, entries),
+            };
+            print_aligned_row(name, value, width);
+        }
+        print_divider();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use aws_sdk_sfn::{
+        primitives::DateTime,
+        types::{
+            builders::HistoryEventBuilder, ExecutionFailedEventDetails, MapIterationEventDetails,
+            MapStateStartedEventDetails, StateEnteredEventDetails, StateExitedEventDetails,
+            TaskFailedEventDetails,
+        },
+    };
+
+    const DEFINITION: &str = r#"{
+        "States": {
+            "Transcribe": {"Type": "Task"},
+            "Analysis": {
+                "Type": "Parallel",
+                "Branches": [
+                    {"States": {"Summarize": {"Type": "Map"}}},
+                    {"States": {"Comprehend": {"Type": "Map"}}}
+                ]
+            },
+            "Compile": {"Type": "Task"}
+        }
+    }"#;
+
+    /// Builds a history where each event follows the one before it.
+    #[derive(Default)]
+    struct History {
+        events: Vec<HistoryEvent>,
+    }
+
+    impl History {
+        fn push(
+            mut self,
+            kind: HistoryEventType,
+            seconds: i64,
+            details: impl FnOnce(HistoryEventBuilder) -> HistoryEventBuilder,
+        ) -> Self {
+            let id = self.events.len() as i64 + 1;
+            let event = HistoryEvent::builder()
+                .id(id)
+                .previous_event_id(id - 1)
+                .r#type(kind)
+                .timestamp(DateTime::from_secs(seconds));
+            self.events.push(details(event).build());
+            self
+        }
+
+        fn event(self, kind: HistoryEventType, seconds: i64) -> Self {
+            self.push(kind, seconds, |event| event)
+        }
+
+        fn entered(self, name: &str, seconds: i64) -> Self {
+            self.push(HistoryEventType::TaskStateEntered, seconds, |event| {
+                event.state_entered_event_details(
+                    StateEnteredEventDetails::builder().name(name).build(),
+                )
+            })
+        }
+
+        fn exited(self, name: &str, seconds: i64) -> Self {
+            self.push(HistoryEventType::TaskStateExited, seconds, |event| {
+                event.state_exited_event_details(
+                    StateExitedEventDetails::builder().name(name).build(),
+                )
+            })
+        }
+
+        fn map_started(self, length: i32, seconds: i64) -> Self {
+            self.push(HistoryEventType::MapStateStarted, seconds, |event| {
+                event.map_state_started_event_details(
+                    MapStateStartedEventDetails::builder()
+                        .length(length)
+                        .build(),
+                )
+            })
+        }
+
+        fn iteration(self, kind: HistoryEventType, map: &str, seconds: i64) -> Self {
+            let details = MapIterationEventDetails::builder().name(map).build();
+            self.push(kind.clone(), seconds, |event| match kind {
+                HistoryEventType::MapIterationFailed => {
+                    event.map_iteration_failed_event_details(details)
+                }
+                _ => event.map_iteration_succeeded_event_details(details),
+            })
+        }
+
+        fn task_failed(self, error: &str, cause: &str, seconds: i64) -> Self {
+            self.push(HistoryEventType::TaskFailed, seconds, |event| {
+                event.task_failed_event_details(
+                    TaskFailedEventDetails::builder()
+                        .error(error)
+                        .cause(cause)
+                        .build(),
+                )
+            })
+        }
+
+        fn execution_failed(self, error: &str, cause: &str, seconds: i64) -> Self {
+            self.push(HistoryEventType::ExecutionFailed, seconds, |event| {
+                event.execution_failed_event_details(
+                    ExecutionFailedEventDetails::builder()
+                        .error(error)
+                        .cause(cause)
+                        .build(),
+                )
+            })
+        }
+
+        fn progress(&self) -> ExecutionProgress {
+            let mut progress = ExecutionProgress::new(DEFINITION);
+            progress.apply(&self.events);
+            progress
+        }
+    }
+
+    #[test]
+    fn top_level_states_become_timed_phases() {
+        let history = History::default()
+            .event(HistoryEventType::ExecutionStarted, 0)
+            .entered("Transcribe", 0)
+            .exited("Transcribe", 10)
+            .entered("Transcribe", 10)
+            .exited("Transcribe", 25)
+            .entered("Analysis", 25);
+        let progress = history.progress();
+
+        assert_eq!(progress.last_event_id(), 6);
+        assert_eq!(progress.phases.len(), 1);
+        assert_eq!(progress.phases[0].name, "Transcribe");
+        assert_eq!(progress.phases[0].seconds, 25.0);
+        assert_eq!(progress.phases[0].entries, 2);
+        assert!(progress.message().starts_with("Analysis ("));
+    }
+
+    #[test]
+    fn between_top_level_states_the_last_phase_is_shown() {
+        assert_eq!(History::default().progress().message(), "starting...");
+
+        let progress = History::default()
+            .entered("Transcribe", 0)
+            .exited("Transcribe", 10)
+            .progress();
+        assert_eq!(progress.message(), "Transcribe");
+    }
+
+    #[test]
+    fn map_iterations_are_counted_in_their_branch() {
+        let history = History::default()
+            .entered("Analysis", 0)
+            .entered("Summarize", 1)
+            .map_started(3, 1)
+            .iteration(HistoryEventType::MapIterationSucceeded, "Summarize", 2)
+            .iteration(HistoryEventType::MapIterationFailed, "Summarize", 3)
+            .entered("Comprehend", 3);
+        let progress = history.progress();
+
+        assert_eq!(progress.maps["Summarize"], (2, 3));
+        assert!(progress
+            .message()
+            .ends_with(" · Summarize 2/3 · Comprehend"));
+
+        let progress = history.exited("Summarize", 4).progress();
+        assert!(progress.message().ends_with(" · Comprehend"));
+    }
+
+    #[test]
+    fn a_failed_task_explains_a_failed_execution() {
+        let progress = History::default()
+            .entered("Compile", 0)
+            .event(HistoryEventType::TaskScheduled, 0)
+            .task_failed(
+                "Lambda.Unknown",
+                r#"{"errorMessage": "boom", "trace": []}"#,
+                1,
+            )
+            .execution_failed("Lambda.Unknown", "{}", 1)
+            .progress();
+
+        let failure = progress.failure().unwrap();
+        assert_eq!(failure.state, "Compile");
+        assert_eq!(failure.error, "Lambda.Unknown");
+        assert_eq!(failure.cause, "boom");
+    }
+
+    #[test]
+    fn retried_tasks_that_succeed_are_not_blamed() {
+        let history = History::default()
+            .entered("Compile", 0)
+            .event(HistoryEventType::TaskScheduled, 0)
+            .task_failed("Lambda.ServiceException", "throttled", 1)
+            .event(HistoryEventType::TaskScheduled, 2)
+            .event(HistoryEventType::TaskSucceeded, 3);
+        assert!(history.progress().failure().is_none());
+
+        let progress = history
+            .exited("Compile", 3)
+            .execution_failed("States.Runtime", "bad path", 3)
+            .progress();
+        let failure = progress.failure().unwrap();
+        assert_eq!(failure.error, "States.Runtime");
+        assert_eq!(failure.cause, "bad path");
+    }
+
+    #[test]
+    fn caught_failures_are_not_blamed_for_a_later_one() {
+        let progress = History::default()
+            .entered("Transcribe", 0)
+            .task_failed("Transcribe.BadRequest", "no such job", 1)
+            .exited("Transcribe", 1)
+            .entered("Compile", 2)
+            .execution_failed("States.Runtime", "bad path", 3)
+            .progress();
+
+        let failure = progress.failure().unwrap();
+        assert_eq!(failure.state, "Compile");
+        assert_eq!(failure.error, "States.Runtime");
+    }
+}
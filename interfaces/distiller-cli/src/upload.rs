@@ -0,0 +1,434 @@
+use crate::{config::state_dir, executions::fnv1a};
+use anyhow::{Context, Result};
+use aws_sdk_s3::{
+    primitives::ByteStream,
+    types::{ChecksumAlgorithm, CompletedMultipartUpload, CompletedPart},
+    Client as S3Client,
+};
+use futures::{stream, StreamExt, TryStreamExt};
+use indicatif::{ProgressBar, ProgressStyle};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::BTreeMap,
+    fs::Metadata,
+    io::SeekFrom,
+    path::{Path, PathBuf},
+    time::UNIX_EPOCH,
+};
+use tokio::{
+    fs,
+    io::{AsyncReadExt, AsyncSeekExt},
+};
+use tracing::debug;
+
+const MIB: u64 = 1024 * 1024;
+/// Files up to this size are sent in a single request.
+const PART_SIZE: u64 = 8 * MIB;
+/// The most parts S3 accepts in one upload; larger files get larger parts.
+const MAX_PARTS: u64 = 10_000;
+/// Parts in flight at once, which bounds memory use to this many parts.
+const UPLOAD_CONCURRENCY: usize = 4;
+const PROGRESS_TEMPLATE: &str =
+    "{prefix:.bold.dim} [{bar:30.blue}] {bytes}/{total_bytes} {bytes_per_sec} ({eta})";
+
+/// A multipart upload in progress, saved after it starts so an interrupted upload of the same
+/// unchanged file can pick up where it left off.
+#[derive(Debug, Deserialize, Serialize)]
+struct UploadState {
+    bucket: String,
+    key: String,
+    file: PathBuf,
+    size: u64,
+    /// Modification time of the file in seconds since the epoch.
+    modified: u64,
+    part_size: u64,
+    upload_id: String,
+}
+
+impl UploadState {
+    /// Where the state of uploading a file to an object is saved.
+    fn path(bucket: &str, key: &str, file: &Path) -> Result<PathBuf> {
+        let id = fnv1a(format!("{}/{}\n{}", bucket, key, file.display()).as_bytes());
+        Ok(state_dir()?
+            .join("uploads")
+            .join(format!("{:016x}.json", id)))
+    }
+
+    /// Reads saved state, ignoring files that are missing or unreadable.
+    async fn load(path: &Path) -> Option<Self> {
+        let content = fs::read(path).await.ok()?;
+        serde_json::from_slice(&content).ok()
+    }
+
+    async fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await.with_context(|| {
+                format!("Failed to create upload state directory: {:?}", parent)
+            })?;
+        }
+
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .await
+            .with_context(|| format!("Failed to save upload state: {:?}", path))
+    }
+
+    /// Whether saved state is for the same upload of the same, unchanged file.
+    fn resumes(&self, saved: &Self) -> bool {
+        self.bucket == saved.bucket
+            && self.key == saved.key
+            && self.file == saved.file
+            && self.size == saved.size
+            && self.modified == saved.modified
+            && self.part_size == saved.part_size
+    }
+
+    fn part_count(&self) -> i32 {
+        self.size.div_ceil(self.part_size) as i32
+    }
+
+    /// The byte offset and length of a part, numbered from 1.
+    fn part_range(&self, part_number: i32) -> (u64, usize) {
+        let offset = (part_number as u64 - 1) * self.part_size;
+        (offset, self.part_size.min(self.size - offset) as usize)
+    }
+}
+
+/// Splits files into at most [`MAX_PARTS`] parts of whole mebibytes, at least [`PART_SIZE`] each.
+fn part_size(size: u64) -> u64 {
+    size.div_ceil(MAX_PARTS).div_ceil(MIB).max(PART_SIZE / MIB) * MIB
+}
+
+/// The modification time of a file in seconds since the epoch, or 0 where it's unavailable.
+pub(crate) fn modified_secs(metadata: &Metadata) -> u64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|time| time.duration_since(UNIX_EPOCH).ok())
+        .map_or(0, |time| time.as_secs())
+}
+
+/// A progress bar of bytes sent, labelled with its prefix.
+pub(crate) fn upload_style() -> Result<ProgressStyle> {
+    Ok(ProgressStyle::default_bar()
+        .template(PROGRESS_TEMPLATE)
+        .context("Failed to create progress style")?
+        .progress_chars("=> "))
+}
+
+/// Uploads an audio file, streaming large files in parts that are sent in parallel and checked
+/// against their SHA-256 checksums by S3. If an upload is interrupted, running it again for the
+/// same unchanged file only sends the parts S3 doesn't have yet. Bytes sent are counted on
+/// `progress`, which is left for the caller to finish.
+pub(crate) async fn upload_file(
+    s3_client: &S3Client,
+    bucket: &str,
+    key: &str,
+    file: &Path,
+    progress: &ProgressBar,
+) -> Result<()> {
+    let metadata = fs::metadata(file)
+        .await
+        .with_context(|| format!("Failed to read file: {:?}", file))?;
+    let size = metadata.len();
+    progress.set_length(size);
+
+    if size <= PART_SIZE {
+        let content = fs::read(file)
+            .await
+            .with_context(|| format!("Failed to read file: {:?}", file))?;
+
+        s3_client
+            .put_object()
+            .bucket(bucket)
+            .key(key)
+            .checksum_algorithm(ChecksumAlgorithm::Sha256)
+            .body(ByteStream::from(content))
+            .send()
+            .await
+            .context("Failed to upload file")?;
+
+        progress.set_position(size);
+        return Ok(());
+    }
+
+    let file = fs::canonicalize(file)
+        .await
+        .with_context(|| format!("Failed to read file: {:?}", file))?;
+    let modified = modified_secs(&metadata);
+    let state_path = UploadState::path(bucket, key, &file)?;
+
+    let mut state = UploadState {
+        bucket: bucket.to_string(),
+        key: key.to_string(),
+        file,
+        size,
+        modified,
+        part_size: part_size(size),
+        upload_id: String::new(),
+    };
+
+    let mut completed = BTreeMap::new();
+    match UploadState::load(&state_path).await {
+        Some(saved) if state.resumes(&saved) => {
+            state.upload_id = saved.upload_id;
+            match uploaded_parts(s3_client, &state).await {
+                Ok(parts) => completed = parts,
+                Err(err) => {
+                    debug!("Could not resume upload {}: {:#}", state.upload_id, err);
+                    state.upload_id.clear();
+                }
+            }
+        }
+        Some(stale) => {
+            // The file changed since the last attempt, so its parts are of no use
+            let aborted = s3_client
+                .abort_multipart_upload()
+                .bucket(&stale.bucket)
+                .key(&stale.key)
+                .upload_id(&stale.upload_id)
+                .send()
+                .await;
+            debug!(
+                "Aborted stale upload {}: {:?}",
+                stale.upload_id,
+                aborted.is_ok()
+            );
+        }
+        None => {}
+    }
+
+    if state.upload_id.is_empty() {
+        let upload = s3_client
+            .create_multipart_upload()
+            .bucket(bucket)
+            .key(key)
+            .checksum_algorithm(ChecksumAlgorithm::Sha256)
+            .send()
+            .await
+            .context("Failed to start upload")?;
+
+        state.upload_id = upload.upload_id().context("Missing upload ID")?.to_string();
+        state.save(&state_path).await?;
+    }
+
+    progress.set_position(
+        completed
+            .keys()
+            .map(|&part_number| state.part_range(part_number).1 as u64)
+            .sum(),
+    );
+    if !completed.is_empty() {
+        progress.println(format!(
+            "Resuming upload with {} of {} parts already sent",
+            completed.len(),
+            state.part_count()
+        ));
+    }
+
+    let remaining: Vec<i32> = (1..=state.part_count())
+        .filter(|part_number| !completed.contains_key(part_number))
+        .collect();
+
+    let result = stream::iter(remaining)
+        .map(|part_number| upload_part(s3_client, &state, part_number))
+        .buffer_unordered(UPLOAD_CONCURRENCY)
+        .try_for_each(|part| {
+            progress.inc(state.part_range(part.part_number()).1 as u64);
+            completed.insert(part.part_number(), part);
+            futures::future::ok(())
+        })
+        .await;
+
+    result.context("Upload interrupted; run the same command again to resume it")?;
+
+    s3_client
+        .complete_multipart_upload()
+        .bucket(bucket)
+        .key(key)
+        .upload_id(&state.upload_id)
+        .multipart_upload(
+            CompletedMultipartUpload::builder()
+                .set_parts(Some(completed.into_values().collect()))
+                .build(),
+        )
+        .send()
+        .await
+        .context("Failed to complete upload")?;
+
+    if let Err(err) = fs::remove_file(&state_path).await {
+        debug!("Failed to remove upload state {:?}: {}", state_path, err);
+    }
+
+    Ok(())
+}
+
+/// The parts S3 already has for a saved upload, keyed by part number. Parts of an unexpected
+/// size are left out so they're sent again.
+async fn uploaded_parts(
+    s3_client: &S3Client,
+    state: &UploadState,
+) -> Result<BTreeMap<i32, CompletedPart>> {
+    let mut parts = s3_client
+        .list_parts()
+        .bucket(&state.bucket)
+        .key(&state.key)
+        .upload_id(&state.upload_id)
+        .into_paginator()
+        .items()
+        .send();
+
+    let mut completed = BTreeMap::new();
+    while let Some(part) = parts.next().await {
+        let part = part.context("Failed to list uploaded parts")?;
+        let part_number = part.part_number();
+
+        if part_number > state.part_count()
+            || part.size() as u64 != state.part_range(part_number).1 as u64
+        {
+            continue;
+        }
+
+        completed.insert(
+            part_number,
+            CompletedPart::builder()
+                .part_number(part_number)
+                .set_e_tag(part.e_tag().map(String::from))
+                .set_checksum_sha256(part.checksum_sha256().map(String::from))
+                .build(),
+        );
+    }
+
+    Ok(completed)
+}
+
+async fn upload_part(
+    s3_client: &S3Client,
+    state: &UploadState,
+    part_number: i32,
+) -> Result<CompletedPart> {
+    let (offset, length) = state.part_range(part_number);
+
+    let mut file = fs::File::open(&state.file)
+        .await
+        .with_context(|| format!("Failed to open file: {:?}", state.file))?;
+    file.seek(SeekFrom::Start(offset)).await?;
+    let mut content = vec![0; length];
+    file.read_exact(&mut content)
+        .await
+        .with_context(|| format!("Failed to read part {} of {:?}", part_number, state.file))?;
+
+    let part = s3_client
+        .upload_part()
+        .bucket(&state.bucket)
+        .key(&state.key)
+        .upload_id(&state.upload_id)
+        .part_number(part_number)
+        .checksum_algorithm(ChecksumAlgorithm::Sha256)
+        .body(ByteStream::from(content))
+        .send()
+        .await
+        .with_context(|| format!("Failed to upload part {}", part_number))?;
+
+    Ok(CompletedPart::builder()
+        .part_number(part_number)
+        .set_e_tag(part.e_tag().map(String::from))
+        .set_checksum_sha256(part.checksum_sha256().map(String::from))
+        .build())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn state(size: u64) -> UploadState {
+        UploadState {
+            bucket: "meetings".to_string(),
+            key: "standup.m4a".to_string(),
+            file: PathBuf::from("/recordings/standup.m4a"),
+            size,
+            modified: 1_733_448_744,
+            part_size: part_size(size),
+            upload_id: "upload-1".to_string(),
+        }
+    }
+
+    #[test]
+    fn parts_are_at_least_the_minimum_size() {
+        assert_eq!(part_size(PART_SIZE + 1), PART_SIZE);
+        assert_eq!(part_size(10 * 1024 * MIB), PART_SIZE);
+    }
+
+    #[test]
+    fn the_last_part_is_short() {
+        let state = state(2 * PART_SIZE + 5);
+
+        assert_eq!(state.part_count(), 3);
+        assert_eq!(state.part_range(1), (0, PART_SIZE as usize));
+        assert_eq!(state.part_range(2), (PART_SIZE, PART_SIZE as usize));
+        assert_eq!(state.part_range(3), (2 * PART_SIZE, 5));
+    }
+
+    #[test]
+    fn an_exact_multiple_has_no_short_part() {
+        let state = state(3 * PART_SIZE);
+
+        assert_eq!(state.part_count(), 3);
+        assert_eq!(state.part_range(3), (2 * PART_SIZE, PART_SIZE as usize));
+    }
+
+    #[test]
+    fn parts_grow_past_the_part_limit() {
+        let at_limit = state(MAX_PARTS * PART_SIZE);
+        assert_eq!(at_limit.part_size, PART_SIZE);
+        assert_eq!(at_limit.part_count(), MAX_PARTS as i32);
+
+        let over_limit = state(MAX_PARTS * PART_SIZE + 1);
+        assert_eq!(over_limit.part_size, PART_SIZE + MIB);
+        assert!(over_limit.part_count() <= MAX_PARTS as i32);
+
+        // The largest object S3 accepts
+        let largest = state(5 * 1024 * 1024 * MIB);
+        assert_eq!(largest.part_size % MIB, 0);
+        assert!(largest.part_count() <= MAX_PARTS as i32);
+        let (offset, length) = largest.part_range(largest.part_count());
+        assert_eq!(offset + length as u64, largest.size);
+    }
+
+    #[test]
+    fn resumes_the_same_unchanged_upload() {
+        let saved = state(3 * PART_SIZE);
+        let current = UploadState {
+            upload_id: String::new(),
+            ..state(3 * PART_SIZE)
+        };
+
+        assert!(current.resumes(&saved));
+    }
+
+    #[test]
+    fn does_not_resume_after_the_file_changed() {
+        let saved = state(3 * PART_SIZE);
+        let changed = [
+            UploadState {
+                size: saved.size + 1,
+                ..state(3 * PART_SIZE)
+            },
+            UploadState {
+                modified: saved.modified + 1,
+                ..state(3 * PART_SIZE)
+            },
+            UploadState {
+                part_size: saved.part_size + MIB,
+                ..state(3 * PART_SIZE)
+            },
+            UploadState {
+                key: "standup-2.m4a".to_string(),
+                ..state(3 * PART_SIZE)
+            },
+        ];
+
+        for current in changed {
+            assert!(!current.resumes(&saved), "{:?} resumed", current);
+        }
+    }
+}
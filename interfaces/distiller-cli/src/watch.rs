@@ -0,0 +1,538 @@
+use crate::{
+    args::{ProcessArgs, WatchArgs},
+    batch::is_audio,
+    client::Client,
+    config::state_dir,
+    display::{
+        print_divider, print_error, print_header, print_success, print_table_row, print_warning,
+    },
+    executions::fnv1a,
+    output::print_output,
+    upload::modified_secs,
+};
+use anyhow::{Context, Result};
+use console::style;
+use futures::{stream::FuturesUnordered, StreamExt};
+use indicatif::{MultiProgress, ProgressBar};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::{BTreeMap, HashMap, HashSet},
+    num::NonZeroUsize,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+use tokio::{fs, signal, time::interval};
+
+const SCAN_INTERVAL: u64 = 5;
+/// The most times the settle time is doubled for a file that keeps failing to be processed.
+const MAX_BACKOFF_DOUBLINGS: u32 = 6;
+
+/// The size and modification time of a recording, which tell a finished file from one still
+/// being written, and a replaced file from one already processed.
+#[derive(Clone, Copy, Debug, Deserialize, Serialize, PartialEq)]
+struct FileVersion {
+    size: u64,
+    modified: u64,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+struct ProcessedFile {
+    #[serde(flatten)]
+    version: FileVersion,
+    succeeded: bool,
+}
+
+/// The recordings a watched directory has already had processed, saved after each one so a
+/// restarted watch doesn't process them again.
+#[derive(Debug, Default, Deserialize, Serialize)]
+struct WatchState {
+    files: BTreeMap<String, ProcessedFile>,
+}
+
+impl WatchState {
+    /// Where the state of watching a directory for a bucket is saved.
+    fn path(bucket: &str, dir: &Path) -> Result<PathBuf> {
+        let id = fnv1a(format!("{}\n{}", bucket, dir.display()).as_bytes());
+        Ok(state_dir()?.join("watch").join(format!("{:016x}.json", id)))
+    }
+
+    async fn load(path: &Path) -> Result<Self> {
+        match fs::read(path).await {
+            Ok(content) => serde_json::from_slice(&content)
+                .with_context(|| format!("Invalid watch state: {:?}", path)),
+            Err(err) if err.kind() == std::io::ErrorKind::NotFound => Ok(Self::default()),
+            Err(err) => Err(err).with_context(|| format!("Failed to read watch state: {:?}", path)),
+        }
+    }
+
+    async fn save(&self, path: &Path) -> Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .await
+                .with_context(|| format!("Failed to create watch state directory: {:?}", parent))?;
+        }
+
+        fs::write(path, serde_json::to_vec_pretty(self)?)
+            .await
+            .with_context(|| format!("Failed to save watch state: {:?}", path))
+    }
+}
+
+/// Follows the recordings of a directory from scan to scan, to tell when each is due.
+#[derive(Default)]
+struct Tracker {
+    /// Files waiting to settle, with the version last seen and when it was first seen
+    pending: HashMap<String, (FileVersion, Instant)>,
+    /// Files being processed
+    running: HashSet<String>,
+    /// How many times in a row each file failed to be processed for a reason other than its
+    /// job failing, such as a network error
+    failures: HashMap<String, u32>,
+}
+
+impl Tracker {
+    /// How long a file must hold still before it's processed: the settle time, doubled for each
+    /// failure in a row so files that keep failing are retried less and less often.
+    fn wait(&self, name: &str, settle: Duration) -> Duration {
+        let doublings = self
+            .failures
+            .get(name)
+            .copied()
+            .unwrap_or_default()
+            .min(MAX_BACKOFF_DOUBLINGS);
+        settle.saturating_mul(1 << doublings)
+    }
+
+    /// Takes up to `limit` files from a scan that have settled and aren't processed yet, and
+    /// marks them running. Files already processed in their current version are skipped, and
+    /// the rest wait, starting over whenever they change.
+    fn take_ready(
+        &mut self,
+        files: BTreeMap<String, FileVersion>,
+        state: &WatchState,
+        settle: Duration,
+        now: Instant,
+        limit: usize,
+    ) -> Vec<(String, FileVersion)> {
+        self.pending.retain(|name, _| files.contains_key(name));
+
+        let mut ready = Vec::new();
+        for (name, version) in files {
+            let processed = state
+                .files
+                .get(&name)
+                .is_some_and(|processed| processed.version == version);
+            if processed || self.running.contains(&name) {
+                continue;
+            }
+
+            match self.pending.get(&name) {
+                Some((seen, since)) if *seen == version => {
+                    if ready.len() < limit && now.duration_since(*since) >= self.wait(&name, settle)
+                    {
+                        ready.push((name, version));
+                    }
+                }
+                _ => {
+                    self.pending.insert(name, (version, now));
+                }
+            }
+        }
+
+        for (name, _) in &ready {
+            self.pending.remove(name);
+            self.running.insert(name.clone());
+        }
+        ready
+    }
+
+    /// Records that a file is no longer running. Only files whose job ran are saved as
+    /// processed; any other failure leaves the file to settle and be tried again.
+    fn finish(&mut self, name: &str, job_ran: bool) {
+        self.running.remove(name);
+        if job_ran {
+            self.failures.remove(name);
+        } else {
+            *self.failures.entry(name.to_string()).or_default() += 1;
+        }
+    }
+}
+
+/// The audio files directly in a directory, by name.
+async fn scan(dir: &Path) -> Result<BTreeMap<String, FileVersion>> {
+    let mut entries = fs::read_dir(dir)
+        .await
+        .with_context(|| format!("Failed to read directory: {:?}", dir))?;
+
+    let mut files = BTreeMap::new();
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') || !is_audio(&path) {
+            continue;
+        }
+
+        // Files can disappear between listing and reading them
+        let Ok(metadata) = entry.metadata().await else {
+            continue;
+        };
+        if metadata.is_file() {
+            files.insert(
+                name,
+                FileVersion {
+                    size: metadata.len(),
+                    modified: modified_secs(&metadata),
+                },
+            );
+        }
+    }
+
+    Ok(files)
+}
+
+/// The reports folder next to a watched directory, such as `recordings-reports` for `recordings`.
+fn sibling_output(dir: &Path) -> PathBuf {
+    let name = dir.file_name().unwrap_or_default().to_string_lossy();
+    dir.with_file_name(format!("{}-reports", name))
+}
+
+/// Watches a directory for recordings and runs each through the pipeline once it has finished
+/// being written, which is when its size and modification time have held for the settle time.
+/// Reports and transcripts are saved to the output folder. Runs until interrupted.
+pub(crate) async fn watch(client: &Client, bucket: &str, args: WatchArgs) -> Result<()> {
+    let dir = fs::canonicalize(&args.dir)
+        .await
+        .with_context(|| format!("Directory does not exist: {:?}", args.dir))?;
+    let output = args.save.clone().unwrap_or_else(|| sibling_output(&dir));
+    fs::create_dir_all(&output)
+        .await
+        .with_context(|| format!("Failed to create output directory: {:?}", output))?;
+
+    let state_path = WatchState::path(bucket, &dir)?;
+    let mut state = WatchState::load(&state_path).await?;
+
+    print_header("Watching");
+    print_divider();
+    print_table_row("Directory:", dir.display().to_string());
+    print_table_row("Output:", output.display().to_string());
+    print_table_row("Bucket:", bucket);
+    print_table_row("Settle Time:", format!("{}s", args.settle.as_secs()));
+    print_table_row("Concurrency:", args.concurrency.to_string());
+    print_table_row("Processed:", state.files.len().to_string());
+    print_divider();
+
+    let watching = process_settled_files(
+        client,
+        bucket,
+        &dir,
+        &output,
+        &args,
+        &mut state,
+        &state_path,
+    );
+    tokio::select! {
+        result = watching => result,
+        _ = signal::ctrl_c() => {
+            print_success(format!("Stopped watching {:?}", dir));
+            Ok(())
+        }
+    }
+}
+
+/// Scans the directory every few seconds and processes each recording once it has settled, up
+/// to `--concurrency` at a time with a progress row each, until an error stops the watch.
+async fn process_settled_files(
+    client: &Client,
+    bucket: &str,
+    dir: &Path,
+    output: &Path,
+    args: &WatchArgs,
+    state: &mut WatchState,
+    state_path: &Path,
+) -> Result<()> {
+    let rows = MultiProgress::new();
+    let mut tracker = Tracker::default();
+    let mut running = FuturesUnordered::new();
+    let mut scans = interval(Duration::from_secs(SCAN_INTERVAL));
+
+    loop {
+        tokio::select! {
+            _ = scans.tick() => {
+                // The directory may be briefly unavailable, such as a network share reconnecting
+                let files = match scan(dir).await {
+                    Ok(files) => files,
+                    Err(err) => {
+                        rows.suspend(|| {
+                            print_warning(format!("{:#}; trying again on the next scan", err))
+                        });
+                        tracing::warn!("{}", err);
+                        continue;
+                    }
+                };
+                let limit = args.concurrency.get() - running.len();
+                for (name, version) in
+                    tracker.take_ready(files, state, args.settle, Instant::now(), limit)
+                {
+                    let process_args = ProcessArgs {
+                        file: Some(dir.join(&name)),
+                        key: None,
+                        keys: args.keys.clone(),
+                        batch: Vec::new(),
+                        concurrency: NonZeroUsize::MIN,
+                        language: args.language.clone(),
+                        wait: true,
+                        transcript_output: Some(
+                            output.join(format!("{}-transcript.txt", name)),
+                        ),
+                        report_output: Some(output.join(format!("{}-report.md", name))),
+                        sentiment_aggregation: None,
+                        entity_aliases: None,
+                        report_config: None,
+                    };
+                    let row = rows.add(ProgressBar::new_spinner());
+                    row.set_prefix(name.clone());
+
+                    running.push(async move {
+                        let processed = client
+                            .process_file_on_row(bucket, &process_args, &row)
+                            .await;
+                        if processed.is_err() {
+                            row.abandon_with_message(style("failed").red().to_string());
+                        }
+                        (name, version, processed)
+                    });
+                }
+            }
+            Some((name, version, processed)) = running.next() => {
+                let succeeded = match processed {
+                    Ok(processed) => {
+                        print_output(&processed)?;
+                        if !processed.succeeded() {
+                            rows.suspend(|| {
+                                print_error(format!("{}: job {}", name, processed.status()))
+                            });
+                        }
+                        processed.succeeded()
+                    }
+                    Err(err) => {
+                        rows.suspend(|| {
+                            print_error(format!(
+                                "{}: {:#}; retrying once it settles again",
+                                name, err
+                            ))
+                        });
+                        tracing::error!("{}: {}", name, err);
+                        tracker.finish(&name, false);
+                        continue;
+                    }
+                };
+                tracker.finish(&name, true);
+
+                // Files whose job failed are retried only once they change, rather than on
+                // every scan
+                state
+                    .files
+                    .insert(name, ProcessedFile { version, succeeded });
+                state.save(state_path).await?;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const SETTLE: Duration = Duration::from_secs(30);
+
+    fn version(size: u64) -> FileVersion {
+        FileVersion { size, modified: 1 }
+    }
+
+    fn files(entries: &[(&str, u64)]) -> BTreeMap<String, FileVersion> {
+        entries
+            .iter()
+            .map(|&(name, size)| (name.to_string(), version(size)))
+            .collect()
+    }
+
+    fn names(ready: Vec<(String, FileVersion)>) -> Vec<String> {
+        ready.into_iter().map(|(name, _)| name).collect()
+    }
+
+    #[test]
+    fn sibling_output_sits_next_to_the_directory() {
+        assert_eq!(
+            sibling_output(Path::new("/home/me/recordings")),
+            PathBuf::from("/home/me/recordings-reports")
+        );
+        assert_eq!(
+            sibling_output(Path::new("recordings")),
+            PathBuf::from("recordings-reports")
+        );
+    }
+
+    #[test]
+    fn files_are_ready_once_they_hold_for_the_settle_time() {
+        let (mut tracker, state, start) =
+            (Tracker::default(), WatchState::default(), Instant::now());
+        let scan = files(&[("a.mp3", 10)]);
+
+        assert!(tracker
+            .take_ready(scan.clone(), &state, SETTLE, start, 4)
+            .is_empty());
+        let early = start + SETTLE - Duration::from_secs(1);
+        assert!(tracker
+            .take_ready(scan.clone(), &state, SETTLE, early, 4)
+            .is_empty());
+        assert_eq!(
+            names(tracker.take_ready(scan.clone(), &state, SETTLE, start + SETTLE, 4)),
+            ["a.mp3"]
+        );
+        // Running files aren't taken again
+        assert!(tracker
+            .take_ready(scan, &state, SETTLE, start + SETTLE * 2, 4)
+            .is_empty());
+    }
+
+    #[test]
+    fn files_still_changing_start_settling_over() {
+        let (mut tracker, state, start) =
+            (Tracker::default(), WatchState::default(), Instant::now());
+
+        tracker.take_ready(files(&[("a.mp3", 10)]), &state, SETTLE, start, 4);
+        let grown = start + SETTLE;
+        assert!(tracker
+            .take_ready(files(&[("a.mp3", 20)]), &state, SETTLE, grown, 4)
+            .is_empty());
+        assert!(tracker
+            .take_ready(
+                files(&[("a.mp3", 20)]),
+                &state,
+                SETTLE,
+                grown + SETTLE / 2,
+                4
+            )
+            .is_empty());
+        assert_eq!(
+            names(tracker.take_ready(files(&[("a.mp3", 20)]), &state, SETTLE, grown + SETTLE, 4)),
+            ["a.mp3"]
+        );
+    }
+
+    #[test]
+    fn processed_files_are_skipped_until_they_change() {
+        let (mut tracker, start) = (Tracker::default(), Instant::now());
+        let mut state = WatchState::default();
+        state.files.insert(
+            "a.mp3".to_string(),
+            ProcessedFile {
+                version: version(10),
+                succeeded: false,
+            },
+        );
+
+        tracker.take_ready(files(&[("a.mp3", 10)]), &state, SETTLE, start, 4);
+        assert!(tracker
+            .take_ready(files(&[("a.mp3", 10)]), &state, SETTLE, start + SETTLE, 4)
+            .is_empty());
+
+        tracker.take_ready(files(&[("a.mp3", 11)]), &state, SETTLE, start, 4);
+        assert_eq!(
+            names(tracker.take_ready(files(&[("a.mp3", 11)]), &state, SETTLE, start + SETTLE, 4)),
+            ["a.mp3"]
+        );
+    }
+
+    #[test]
+    fn no_more_than_the_limit_are_taken_at_once() {
+        let (mut tracker, state, start) =
+            (Tracker::default(), WatchState::default(), Instant::now());
+        let scan = files(&[("a.mp3", 1), ("b.mp3", 1), ("c.mp3", 1)]);
+
+        tracker.take_ready(scan.clone(), &state, SETTLE, start, 2);
+        assert_eq!(
+            names(tracker.take_ready(scan.clone(), &state, SETTLE, start + SETTLE, 2)),
+            ["a.mp3", "b.mp3"]
+        );
+        assert!(tracker
+            .take_ready(scan.clone(), &state, SETTLE, start + SETTLE, 0)
+            .is_empty());
+
+        tracker.finish("a.mp3", true);
+        // The file left waiting is due as soon as there's room
+        assert_eq!(
+            names(tracker.take_ready(scan, &state, SETTLE, start + SETTLE, 1)),
+            ["c.mp3"]
+        );
+    }
+
+    #[test]
+    fn failed_attempts_back_off() {
+        let (mut tracker, state, start) =
+            (Tracker::default(), WatchState::default(), Instant::now());
+        let scan = files(&[("a.mp3", 1)]);
+
+        tracker.take_ready(scan.clone(), &state, SETTLE, start, 4);
+        tracker.take_ready(scan.clone(), &state, SETTLE, start + SETTLE, 4);
+        tracker.finish("a.mp3", false);
+
+        // The file settles again, for twice as long
+        let retry = start + SETTLE * 2;
+        tracker.take_ready(scan.clone(), &state, SETTLE, retry, 4);
+        assert!(tracker
+            .take_ready(scan.clone(), &state, SETTLE, retry + SETTLE, 4)
+            .is_empty());
+        assert_eq!(
+            names(tracker.take_ready(scan, &state, SETTLE, retry + SETTLE * 2, 4)),
+            ["a.mp3"]
+        );
+
+        // A job that ran clears the failures
+        tracker.finish("a.mp3", true);
+        assert_eq!(tracker.wait("a.mp3", SETTLE), SETTLE);
+    }
+
+    #[test]
+    fn backoff_is_capped() {
+        let mut tracker = Tracker::default();
+        for _ in 0..100 {
+            tracker.finish("a.mp3", false);
+        }
+        assert_eq!(
+            tracker.wait("a.mp3", SETTLE),
+            SETTLE * (1 << MAX_BACKOFF_DOUBLINGS)
+        );
+    }
+
+    #[test]
+    fn deleted_files_stop_waiting() {
+        let (mut tracker, state, start) =
+            (Tracker::default(), WatchState::default(), Instant::now());
+
+        tracker.take_ready(files(&[("a.mp3", 1)]), &state, SETTLE, start, 4);
+        tracker.take_ready(files(&[]), &state, SETTLE, start + SETTLE, 4);
+        assert!(tracker.pending.is_empty());
+    }
+
+    #[tokio::test]
+    async fn watch_state_is_saved_and_loaded() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("watch").join("state.json");
+        assert!(WatchState::load(&path).await.unwrap().files.is_empty());
+
+        let mut state = WatchState::default();
+        state.files.insert(
+            "a.mp3".to_string(),
+            ProcessedFile {
+                version: version(10),
+                succeeded: true,
+            },
+        );
+        state.save(&path).await.unwrap();
+
+        let loaded = WatchState::load(&path).await.unwrap();
+        assert_eq!(loaded.files["a.mp3"].version, version(10));
+        assert!(loaded.files["a.mp3"].succeeded);
+    }
+}
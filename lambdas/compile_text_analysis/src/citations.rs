@@ -0,0 +1,165 @@
+use crate::transcript::{chunk_anchor, ChunkMetadata};
+use std::collections::BTreeSet;
+
+/// Ranges wider than this aren't plausible citations.
+const MAX_CITED_RANGE: usize = 100;
+
+/// Parses the inside of a citation such as `S1, S3` or `S2-S4` into section numbers.
+fn parse_citation(inner: &str) -> Option<Vec<usize>> {
+    let section = |part: &str| {
+        let part = part.trim();
+        part.strip_prefix(['S', 's'])
+            .unwrap_or(part)
+            .trim()
+            .parse::<usize>()
+            .ok()
+    };
+
+    // Only bracketed text starting with a section marker is a citation
+    if !inner.trim_start().starts_with(['S', 's']) {
+        return None;
+    }
+
+    let mut sections = Vec::new();
+    for part in inner.split(',') {
+        match part.split_once(['-', '–']) {
+            Some((start, end)) => {
+                let (start, end) = (section(start)?, section(end)?);
+                if end < start || end - start > MAX_CITED_RANGE {
+                    return None;
+                }
+                sections.extend(start..=end);
+            }
+            None => sections.push(section(part)?),
+        }
+    }
+
+    (!sections.is_empty()).then_some(sections)
+}
+
+/// Replaces the `[S2]` style citations the synthesis stage adds with links to the cited chunk
+/// summaries, titled with their time ranges. Citations of sections that don't exist are marked
+/// and returned so they can be raised as quality warnings.
+pub(crate) fn link_citations(
+    text: &str,
+    chunks: &[ChunkMetadata],
+    chunk_count: usize,
+) -> (String, BTreeSet<usize>) {
+    let mut linked = String::with_capacity(text.len());
+    let mut unknown = BTreeSet::new();
+    let mut rest = text;
+
+    while let Some(open) = rest.find('[') {
+        linked.push_str(&rest[..open]);
+        let after = &rest[open + 1..];
+
+        let Some((sections, close)) = after
+            .find(']')
+            .and_then(|close| Some((parse_citation(&after[..close])?, close)))
+        else {
+            linked.push('[');
+            rest = after;
+            continue;
+        };
+
+        for section in sections {
+            if (1..=chunk_count).contains(&section) {
+                let index = section - 1;
+                match chunks.get(index).and_then(ChunkMetadata::time_range) {
+                    Some(range) => linked.push_str(&format!(
+                        "[[{}]]({} \"{}\")",
+                        section,
+                        chunk_anchor(index),
+                        range
+                    )),
+                    None => linked.push_str(&format!("[[{}]]({})", section, chunk_anchor(index))),
+                }
+            } else {
+                linked.push_str(&format!("[{}?]", section));
+                unknown.insert(section);
+            }
+        }
+        rest = &after[close + 1..];
+    }
+    linked.push_str(rest);
+
+    (linked, unknown)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(start: f64, end: f64) -> ChunkMetadata {
+        ChunkMetadata {
+            start_time: Some(start),
+            end_time: Some(end),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn parses_lists_and_ranges() {
+        assert_eq!(parse_citation("S1"), Some(vec![1]));
+        assert_eq!(parse_citation("S1, S3"), Some(vec![1, 3]));
+        assert_eq!(parse_citation("s2-s4"), Some(vec![2, 3, 4]));
+        assert_eq!(parse_citation("S2–4, S7"), Some(vec![2, 3, 4, 7]));
+        assert_eq!(parse_citation(" S 5 "), Some(vec![5]));
+    }
+
+    #[test]
+    fn rejects_text_that_is_not_a_citation() {
+        assert_eq!(parse_citation("see notes"), None);
+        assert_eq!(parse_citation("1"), None);
+        assert_eq!(parse_citation(""), None);
+        assert_eq!(parse_citation("S1, later"), None);
+        assert_eq!(parse_citation("Sx"), None);
+    }
+
+    #[test]
+    fn rejects_reversed_and_implausible_ranges() {
+        assert_eq!(parse_citation("S4-S2"), None);
+        assert_eq!(parse_citation("S1-S500"), None);
+        assert_eq!(parse_citation("S1-S101"), Some((1..=101).collect()));
+    }
+
+    #[test]
+    fn links_cited_sections_with_their_time_ranges() {
+        let chunks = [chunk(0.0, 95.0), chunk(95.0, 180.0)];
+        let (linked, unknown) = link_citations("Budget was cut [S1, S2].", &chunks, 2);
+
+        assert_eq!(
+            linked,
+            "Budget was cut [[1]](#chunk-1-summary \"00:00–01:35\")\
+             [[2]](#chunk-2-summary \"01:35–03:00\")."
+        );
+        assert!(unknown.is_empty());
+    }
+
+    #[test]
+    fn links_sections_without_times_plainly() {
+        let (linked, _) = link_citations("Agreed [S2-S3]", &[], 3);
+        assert_eq!(
+            linked,
+            "Agreed [[2]](#chunk-2-summary)[[3]](#chunk-3-summary)"
+        );
+    }
+
+    #[test]
+    fn marks_unknown_sections() {
+        let (linked, unknown) = link_citations("Per [S0] and [S2, S9]", &[], 3);
+        assert_eq!(linked, "Per [0?] and [[2]](#chunk-2-summary)[9?]");
+        assert_eq!(unknown, BTreeSet::from([0, 9]));
+    }
+
+    #[test]
+    fn leaves_other_brackets_alone() {
+        let text = "See [the notes](https://example.com) [TODO] [S1 [S1] trailing [";
+        let (linked, unknown) = link_citations(text, &[], 1);
+        assert_eq!(
+            linked,
+            "See [the notes](https://example.com) [TODO] [S1 [[1]](#chunk-1-summary) trailing ["
+        );
+        assert!(unknown.is_empty());
+    }
+}
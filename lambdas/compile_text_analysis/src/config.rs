@@ -0,0 +1,111 @@
+use crate::entities::Entity;
+use crate::sentiment::SentimentAggregation;
+use serde::Deserialize;
+use serde_json::Value;
+
+/// The sections a report can contain, rendered in the order they are configured.
+#[derive(Debug, Deserialize, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ReportSection {
+    Overview,
+    MainTopics,
+    MindMap,
+    ActionItems,
+    Decisions,
+    OpenQuestions,
+    KeyPhrases,
+    Sentiment,
+    Speakers,
+    Entities,
+    ChunkSummaries,
+}
+
+/// How much of the sentiment analysis is shown.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SentimentStyle {
+    /// Overall verdict, confidence scores and the per-chunk timeline.
+    #[default]
+    Full,
+    /// Overall verdict and confidence scores.
+    Scores,
+    /// A single line with the verdict and the sentiment arc.
+    Compact,
+}
+
+/// Controls which sections appear in the report and how much detail they carry.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub(crate) struct ReportConfig {
+    pub(crate) sections: Vec<ReportSection>,
+    pub(crate) min_entity_confidence: f64,
+    /// Only these entity types are reported when set.
+    pub(crate) entity_types: Option<Vec<String>>,
+    pub(crate) exclude_entity_types: Vec<String>,
+    pub(crate) max_entities_per_type: Option<usize>,
+    pub(crate) min_key_phrase_score: f64,
+    pub(crate) max_key_phrases: usize,
+    /// Chunks transcribed with a lower mean word confidence get a quality warning.
+    pub(crate) min_transcript_confidence: f64,
+    pub(crate) sentiment_style: SentimentStyle,
+    pub(crate) sentiment_aggregation: SentimentAggregation,
+    /// Folds each chunk summary into a `<details>` block.
+    pub(crate) collapse_chunk_summaries: bool,
+    /// Moves the chunk summaries into a separate appendix file linked from the report.
+    pub(crate) chunk_summary_appendix: bool,
+}
+
+impl Default for ReportConfig {
+    fn default() -> Self {
+        Self {
+            sections: vec![
+                ReportSection::Overview,
+                ReportSection::MainTopics,
+                ReportSection::MindMap,
+                ReportSection::ActionItems,
+                ReportSection::Decisions,
+                ReportSection::OpenQuestions,
+                ReportSection::KeyPhrases,
+                ReportSection::Sentiment,
+                ReportSection::Speakers,
+                ReportSection::Entities,
+                ReportSection::ChunkSummaries,
+            ],
+            min_entity_confidence: 0.0,
+            entity_types: None,
+            exclude_entity_types: Vec::new(),
+            max_entities_per_type: None,
+            min_key_phrase_score: 0.0,
+            max_key_phrases: 20,
+            min_transcript_confidence: 0.8,
+            sentiment_style: SentimentStyle::default(),
+            sentiment_aggregation: SentimentAggregation::default(),
+            collapse_chunk_summaries: true,
+            chunk_summary_appendix: false,
+        }
+    }
+}
+
+impl ReportConfig {
+    /// Layers the options from the execution input over the config document loaded from S3.
+    pub(crate) fn resolve(document: &Value, options: &Value) -> Result<Self, serde_json::Error> {
+        let mut merged = document.as_object().cloned().unwrap_or_default();
+        if let Some(options) = options.as_object() {
+            merged.extend(options.clone());
+        }
+
+        serde_json::from_value(Value::Object(merged))
+    }
+
+    pub(crate) fn includes_entity(&self, entity: &Entity) -> bool {
+        let matches = |types: &[String]| {
+            types
+                .iter()
+                .any(|t| t.eq_ignore_ascii_case(&entity.entity_type))
+        };
+
+        entity.score >= self.min_entity_confidence
+            && self.entity_types.as_deref().is_none_or(matches)
+            && !matches(&self.exclude_entity_types)
+    }
+}
@@ -0,0 +1,386 @@
+use crate::citations::link_citations;
+use crate::config::ReportConfig;
+use crate::locale::Locale;
+use crate::outcomes::parse_outcomes;
+use crate::transcript::{chunk_anchor, chunk_reference};
+use crate::{extract_text, AnalysisResult, BedrockWrapper};
+use serde::Serialize;
+
+/// The stop reason Bedrock reports when a reply hit the token limit.
+const TRUNCATED_STOP_REASON: &str = "max_tokens";
+
+/// A reply the pipeline asks the model for.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum ModelOutput {
+    Overview,
+    MainTopics,
+    ChunkSummary,
+    ChunkTopics,
+    MeetingOutcomes,
+}
+
+/// A Comprehend analysis run on every chunk.
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum Analysis {
+    Entities,
+    KeyPhrases,
+    Sentiment,
+}
+
+/// Something in the inputs that makes part of the report less trustworthy. Chunks are numbered
+/// from one, as in the report.
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub(crate) enum QualityWarning {
+    EmptyTranscript,
+    EmptyChunk {
+        chunk: usize,
+    },
+    TruncatedOutput {
+        output: ModelOutput,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        chunk: Option<usize>,
+    },
+    UnreadableOutcomes {
+        chunk: usize,
+    },
+    MissingAnalysis {
+        analysis: Analysis,
+        missing: usize,
+        chunks: usize,
+    },
+    LowConfidence {
+        chunk: usize,
+        confidence: f64,
+    },
+    UnknownCitations {
+        sections: Vec<usize>,
+    },
+}
+
+fn truncated(response: &BedrockWrapper) -> bool {
+    response.body.stop_reason.as_deref() == Some(TRUNCATED_STOP_REASON)
+}
+
+/// Checks the pipeline's outputs for gaps and signs of low quality.
+pub(crate) fn collect_warnings(
+    analysis: &AnalysisResult,
+    config: &ReportConfig,
+) -> Vec<QualityWarning> {
+    let chunk_count = analysis.chunk_summaries.len();
+    let mut warnings = Vec::new();
+
+    if chunk_count == 0 {
+        warnings.push(QualityWarning::EmptyTranscript);
+    }
+
+    for (output, response) in [
+        (ModelOutput::Overview, &analysis.overview),
+        (ModelOutput::MainTopics, &analysis.main_topics),
+    ] {
+        if truncated(response) {
+            warnings.push(QualityWarning::TruncatedOutput {
+                output,
+                chunk: None,
+            });
+        }
+    }
+
+    for (i, chunk) in analysis.chunk_summaries.iter().enumerate() {
+        if chunk.chunk.trim().is_empty() {
+            warnings.push(QualityWarning::EmptyChunk { chunk: i + 1 });
+        }
+
+        // The chunk's replies arrive in the order the Bedrock map asks for them
+        let outputs = [
+            ModelOutput::ChunkSummary,
+            ModelOutput::ChunkTopics,
+            ModelOutput::MeetingOutcomes,
+        ];
+        for (output, response) in outputs.into_iter().zip(&chunk.chunk_analysis) {
+            if truncated(response) {
+                warnings.push(QualityWarning::TruncatedOutput {
+                    output,
+                    chunk: Some(i + 1),
+                });
+            }
+        }
+
+        if let Some(outcomes) = chunk.chunk_analysis.get(2) {
+            if parse_outcomes(&extract_text(outcomes)).is_none() {
+                warnings.push(QualityWarning::UnreadableOutcomes { chunk: i + 1 });
+            }
+        }
+
+        if let Some(confidence) = analysis
+            .chunk_metadata
+            .get(i)
+            .and_then(|metadata| metadata.confidence)
+            .filter(|&confidence| confidence < config.min_transcript_confidence)
+        {
+            warnings.push(QualityWarning::LowConfidence {
+                chunk: i + 1,
+                confidence,
+            });
+        }
+    }
+
+    // Comprehend returns one result per chunk once flattened. Key phrases are only checked when
+    // the pipeline ran them, as older executions have no such field.
+    let key_phrases = analysis
+        .key_phrases
+        .as_ref()
+        .map(|key_phrases| (Analysis::KeyPhrases, key_phrases.iter().map(Vec::len).sum()));
+    let results = [
+        Some((
+            Analysis::Entities,
+            analysis.entities.iter().map(Vec::len).sum(),
+        )),
+        key_phrases,
+        Some((
+            Analysis::Sentiment,
+            analysis.sentiment.iter().map(Vec::len).sum(),
+        )),
+    ];
+    for (kind, results) in results.into_iter().flatten() {
+        let missing = chunk_count.saturating_sub(results);
+        if missing > 0 {
+            warnings.push(QualityWarning::MissingAnalysis {
+                analysis: kind,
+                missing,
+                chunks: chunk_count,
+            });
+        }
+    }
+
+    let (_, unknown) = link_citations(
+        &extract_text(&analysis.overview),
+        &analysis.chunk_metadata,
+        chunk_count,
+    );
+    if !unknown.is_empty() {
+        warnings.push(QualityWarning::UnknownCitations {
+            sections: unknown.into_iter().collect(),
+        });
+    }
+
+    warnings
+}
+
+impl QualityWarning {
+    fn message(&self, locale: &Locale) -> String {
+        let chunk = |n: usize| chunk_reference(n - 1, locale);
+
+        match self {
+            QualityWarning::EmptyTranscript => locale.empty_transcript.to_string(),
+            QualityWarning::EmptyChunk { chunk: n } => (locale.empty_chunk)(&chunk(*n)),
+            QualityWarning::TruncatedOutput { output, chunk: n } => {
+                let name = match (output, n) {
+                    (ModelOutput::Overview, _) => locale.overview.to_string(),
+                    (ModelOutput::MainTopics, _) => locale.main_topics.to_string(),
+                    (ModelOutput::ChunkSummary, Some(n)) => {
+                        format!("[{}]({})", (locale.chunk_summary)(*n), chunk_anchor(*n - 1))
+                    }
+                    (ModelOutput::ChunkTopics, Some(n)) => {
+                        format!("{} ({})", locale.topics, chunk(*n))
+                    }
+                    (ModelOutput::MeetingOutcomes, Some(n)) => format!(
+                        "{}, {}, {} ({})",
+                        locale.action_items,
+                        locale.decisions,
+                        locale.open_questions,
+                        chunk(*n)
+                    ),
+                    (_, None) => String::new(),
+                };
+                (locale.truncated)(&name)
+            }
+            QualityWarning::UnreadableOutcomes { chunk: n } => {
+                (locale.unreadable_outcomes)(&chunk(*n))
+            }
+            QualityWarning::MissingAnalysis {
+                analysis,
+                missing,
+                chunks,
+            } => {
+                let name = match analysis {
+                    Analysis::Entities => locale.named_entities,
+                    Analysis::KeyPhrases => locale.key_phrases,
+                    Analysis::Sentiment => locale.sentiment_analysis,
+                };
+                (locale.missing_analysis)(name, *missing, *chunks)
+            }
+            QualityWarning::LowConfidence {
+                chunk: n,
+                confidence,
+            } => (locale.low_confidence)(&chunk(*n), &format!("{:.0}%", confidence * 100.0)),
+            QualityWarning::UnknownCitations { sections } => {
+                let sections: Vec<String> = sections.iter().map(usize::to_string).collect();
+                (locale.unknown_citations)(&sections.join(", "))
+            }
+        }
+    }
+}
+
+/// Lists the warnings as a section for the top of the report, or nothing when there are none.
+pub(crate) fn format_quality_warnings(warnings: &[QualityWarning], locale: &Locale) -> String {
+    if warnings.is_empty() {
+        return String::new();
+    }
+
+    let items: Vec<String> = warnings
+        .iter()
+        .map(|warning| format!("- ⚠️ {}", warning.message(locale)))
+        .collect();
+
+    format!("## {}\n{}\n\n", locale.quality_warnings, items.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::{json, Value};
+
+    fn reply(text: &str) -> Value {
+        json!({ "Body": { "content": [{ "type": "text", "text": text }] } })
+    }
+
+    /// Two chunks with every analysis present and nothing to warn about.
+    fn payload() -> Value {
+        let chunk = |text: &str| {
+            json!({
+                "chunk": text,
+                "chunkAnalysis": [
+                    reply("A summary."),
+                    reply("- Budget"),
+                    reply(r#"{"action_items": []}"#),
+                ],
+            })
+        };
+        let sentiment = json!([{ "Sentiment": "NEUTRAL", "SentimentScore": {} }]);
+
+        json!({
+            "overview": reply("We agreed on the budget [S1] and hiring [S2]."),
+            "main_topics": reply("- Budget"),
+            "chunk_summaries": [chunk("We went over the budget."), chunk("Then hiring.")],
+            "sentiment": [sentiment, sentiment],
+            "entities": [[{ "Entities": [] }], [{ "Entities": [] }]],
+            "chunk_metadata": [
+                { "begin_offset": 0, "end_offset": 24, "confidence": 0.95 },
+                { "begin_offset": 24, "end_offset": 36, "confidence": 0.9 },
+            ],
+            "key": "standup.mp3",
+        })
+    }
+
+    fn warnings(payload: Value) -> Value {
+        let analysis: AnalysisResult = serde_json::from_value(payload).unwrap();
+        serde_json::to_value(collect_warnings(&analysis, &ReportConfig::default())).unwrap()
+    }
+
+    #[test]
+    fn complete_results_have_no_warnings() {
+        assert_eq!(warnings(payload()), json!([]));
+    }
+
+    #[test]
+    fn warns_about_an_empty_transcript() {
+        let mut payload = payload();
+        payload["chunk_summaries"] = json!([]);
+        payload["overview"] = reply("Nothing was said.");
+
+        assert_eq!(warnings(payload), json!([{ "kind": "empty_transcript" }]));
+    }
+
+    #[test]
+    fn warns_about_empty_chunks() {
+        let mut payload = payload();
+        payload["chunk_summaries"][1]["chunk"] = json!("  ");
+
+        assert_eq!(
+            warnings(payload),
+            json!([{ "kind": "empty_chunk", "chunk": 2 }])
+        );
+    }
+
+    #[test]
+    fn warns_about_truncated_replies() {
+        let mut payload = payload();
+        payload["main_topics"]["Body"]["stop_reason"] = json!("max_tokens");
+        payload["chunk_summaries"][1]["chunkAnalysis"][1]["Body"]["stop_reason"] =
+            json!("max_tokens");
+        payload["chunk_summaries"][0]["chunkAnalysis"][0]["Body"]["stop_reason"] =
+            json!("end_turn");
+
+        assert_eq!(
+            warnings(payload),
+            json!([
+                { "kind": "truncated_output", "output": "main_topics" },
+                { "kind": "truncated_output", "output": "chunk_topics", "chunk": 2 },
+            ])
+        );
+    }
+
+    #[test]
+    fn warns_about_unreadable_outcomes() {
+        let mut payload = payload();
+        payload["chunk_summaries"][0]["chunkAnalysis"][2] = reply("No outcomes here.");
+
+        assert_eq!(
+            warnings(payload),
+            json!([{ "kind": "unreadable_outcomes", "chunk": 1 }])
+        );
+    }
+
+    #[test]
+    fn warns_about_low_confidence_chunks() {
+        let mut payload = payload();
+        payload["chunk_metadata"][1]["confidence"] = json!(0.5);
+
+        assert_eq!(
+            warnings(payload),
+            json!([{ "kind": "low_confidence", "chunk": 2, "confidence": 0.5 }])
+        );
+    }
+
+    #[test]
+    fn missing_metadata_is_not_low_confidence() {
+        let mut unmeasured = payload();
+        unmeasured["chunk_metadata"][1]["confidence"] = Value::Null;
+        assert_eq!(warnings(unmeasured), json!([]));
+
+        let mut unlocated = payload();
+        unlocated["chunk_metadata"] = json!([]);
+        assert_eq!(warnings(unlocated), json!([]));
+    }
+
+    #[test]
+    fn warns_about_missing_analyses() {
+        let mut payload = payload();
+        payload["entities"] = json!([]);
+        payload["key_phrases"] = json!([[{ "KeyPhrases": [] }], []]);
+        payload["sentiment"] = json!([[]]);
+
+        assert_eq!(
+            warnings(payload),
+            json!([
+                { "kind": "missing_analysis", "analysis": "entities", "missing": 2, "chunks": 2 },
+                { "kind": "missing_analysis", "analysis": "key_phrases", "missing": 1, "chunks": 2 },
+                { "kind": "missing_analysis", "analysis": "sentiment", "missing": 2, "chunks": 2 },
+            ])
+        );
+    }
+
+    #[test]
+    fn warns_about_unknown_citations() {
+        let mut payload = payload();
+        payload["overview"] = reply("We agreed on the budget [S1] and hiring [S3, S4].");
+
+        assert_eq!(
+            warnings(payload),
+            json!([{ "kind": "unknown_citations", "sections": [3, 4] }])
+        );
+    }
+}
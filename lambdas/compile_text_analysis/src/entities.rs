@@ -0,0 +1,639 @@
+use crate::config::ReportConfig;
+use crate::locale::Locale;
+use crate::transcript::{position_link, table_cell, ChunkMetadata};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Titles dropped from person names before comparing them.
+const HONORIFICS: [&str; 9] = [
+    "dr",
+    "mr",
+    "mrs",
+    "ms",
+    "miss",
+    "prof",
+    "professor",
+    "sir",
+    "dame",
+];
+
+/// Entity types whose shorter mentions are merged into a unique longer name.
+const PARTIAL_NAME_TYPES: [&str; 2] = ["PERSON", "ORGANIZATION"];
+
+/// Entity types whose trailing `s` is meaningful and must not be singularized.
+const UNINFLECTED_TYPES: [&str; 3] = ["PERSON", "DATE", "QUANTITY"];
+
+/// Characters of surrounding transcript shown on either side of a mention.
+const SNIPPET_CONTEXT: usize = 60;
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct Entity {
+    pub(crate) text: String,
+    #[serde(rename = "Type")]
+    pub(crate) entity_type: String,
+    pub(crate) score: f64,
+    /// Character offsets relative to the chunk Comprehend analyzed.
+    pub(crate) begin_offset: usize,
+    pub(crate) end_offset: usize,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct EntityData {
+    pub(crate) entities: Vec<Entity>,
+}
+
+/// Canonical names mapped to the other ways they are written, loaded from the optional alias file.
+pub(crate) type EntityAliases = BTreeMap<String, Vec<String>>;
+
+/// All mentions that resolved to the same real-world entity.
+#[derive(Debug)]
+struct CanonicalEntity<'a> {
+    entity_type: &'a str,
+    tokens: BTreeSet<String>,
+    canonical_name: Option<&'a str>,
+    mentions: Vec<(&'a Entity, usize)>,
+}
+
+impl CanonicalEntity<'_> {
+    fn variants(&self) -> BTreeMap<&str, usize> {
+        let mut variants = BTreeMap::new();
+        for (entity, _) in &self.mentions {
+            *variants.entry(entity.text.as_str()).or_default() += 1;
+        }
+        variants
+    }
+
+    /// The alias file name if there is one, otherwise the most complete, most frequent variant.
+    fn display_name(&self) -> &str {
+        if let Some(name) = self.canonical_name {
+            return name;
+        }
+
+        self.variants()
+            .into_iter()
+            .max_by(|(a, a_count), (b, b_count)| {
+                normalize(a, self.entity_type)
+                    .len()
+                    .cmp(&normalize(b, self.entity_type).len())
+                    .then(a_count.cmp(b_count))
+                    .then(b.cmp(a))
+            })
+            .map(|(text, _)| text)
+            .unwrap_or_default()
+    }
+
+    fn confidence(&self) -> f64 {
+        self.mentions
+            .iter()
+            .map(|(entity, _)| entity.score)
+            .fold(0.0, f64::max)
+    }
+
+    fn first_mention(&self) -> Option<&(&Entity, usize)> {
+        self.mentions
+            .iter()
+            .min_by_key(|(entity, chunk)| (*chunk, entity.begin_offset))
+    }
+
+    fn last_mention(&self) -> Option<&(&Entity, usize)> {
+        self.mentions
+            .iter()
+            .max_by_key(|(entity, chunk)| (*chunk, entity.begin_offset))
+    }
+}
+
+/// The words around a mention with the mention itself in bold, safe to place in a table cell.
+fn context_snippet(chunk_text: &str, entity: &Entity) -> String {
+    let chars: Vec<char> = chunk_text.chars().collect();
+    if entity.begin_offset >= entity.end_offset || entity.end_offset > chars.len() {
+        return String::new();
+    }
+
+    let mut start = entity.begin_offset.saturating_sub(SNIPPET_CONTEXT);
+    let mut end = (entity.end_offset + SNIPPET_CONTEXT).min(chars.len());
+
+    // Avoid cutting words in half at either edge
+    while start > 0 && start < entity.begin_offset && !chars[start - 1].is_whitespace() {
+        start += 1;
+    }
+    while end < chars.len() && end > entity.end_offset && !chars[end].is_whitespace() {
+        end -= 1;
+    }
+
+    let text = |range: std::ops::Range<usize>| chars[range].iter().collect::<String>();
+    let snippet = format!(
+        "{}{}**{}**{}{}",
+        if start > 0 { "…" } else { "" },
+        text(start..entity.begin_offset),
+        text(entity.begin_offset..entity.end_offset),
+        text(entity.end_offset..end),
+        if end < chars.len() { "…" } else { "" }
+    );
+
+    table_cell(&snippet.split_whitespace().collect::<Vec<&str>>().join(" "))
+}
+
+/// Lowercases and strips punctuation and possessives, optionally singularizing plurals, so
+/// trivially different spellings compare equal.
+pub(crate) fn tokenize(text: &str, singularize: bool) -> Vec<String> {
+    text.to_lowercase()
+        .replace('’', "'")
+        .split(|c: char| c.is_whitespace() || c == '-')
+        .map(|token| token.strip_suffix("'s").unwrap_or(token))
+        .map(|token| {
+            token
+                .chars()
+                .filter(|c| c.is_alphanumeric())
+                .collect::<String>()
+        })
+        .filter(|token| !token.is_empty())
+        .map(|token| {
+            if !singularize || token.len() <= 3 {
+                token
+            } else if let Some(stem) = token.strip_suffix("ies") {
+                format!("{}y", stem)
+            } else if ["ches", "shes", "sses", "xes"]
+                .iter()
+                .any(|suffix| token.ends_with(suffix))
+            {
+                token[..token.len() - 2].to_string()
+            } else if token.ends_with('s') && !token.ends_with("ss") {
+                token[..token.len() - 1].to_string()
+            } else {
+                token
+            }
+        })
+        .collect()
+}
+
+/// Tokenizes an entity name, dropping honorifics from people.
+fn normalize(text: &str, entity_type: &str) -> BTreeSet<String> {
+    let tokens = tokenize(text, !UNINFLECTED_TYPES.contains(&entity_type));
+
+    let without_honorifics: BTreeSet<String> = tokens
+        .iter()
+        .filter(|token| entity_type != "PERSON" || !HONORIFICS.contains(&token.as_str()))
+        .cloned()
+        .collect();
+
+    // A bare title such as "Dr" is still worth keeping as its own entity
+    if without_honorifics.is_empty() {
+        tokens.into_iter().collect()
+    } else {
+        without_honorifics
+    }
+}
+
+/// Every alias and canonical name, normalized for an entity type, mapped to the canonical name.
+/// When two canonical names share an alias, the first one in the file wins.
+fn alias_lookup<'a>(
+    aliases: &'a EntityAliases,
+    entity_type: &str,
+) -> BTreeMap<BTreeSet<String>, &'a str> {
+    let mut lookup = BTreeMap::new();
+    for (canonical, alternatives) in aliases {
+        for alias in std::iter::once(canonical).chain(alternatives) {
+            lookup
+                .entry(normalize(alias, entity_type))
+                .or_insert(canonical.as_str());
+        }
+    }
+    lookup
+}
+
+fn canonicalize<'a>(
+    entities_chunks: &'a [Vec<EntityData>],
+    aliases: &'a EntityAliases,
+    config: &ReportConfig,
+) -> Vec<CanonicalEntity<'a>> {
+    // Aliases normalize differently by entity type, so each type gets its own lookup
+    let mut alias_lookups: BTreeMap<&str, BTreeMap<BTreeSet<String>, &str>> = BTreeMap::new();
+
+    // Comprehend results arrive in chunk order once flattened
+    let mut clusters: BTreeMap<(&str, BTreeSet<String>), CanonicalEntity> = BTreeMap::new();
+    for (chunk, data) in entities_chunks.iter().flatten().enumerate() {
+        for entity in data
+            .entities
+            .iter()
+            .filter(|entity| config.includes_entity(entity))
+        {
+            let mut tokens = normalize(&entity.text, &entity.entity_type);
+            let canonical_name = alias_lookups
+                .entry(&entity.entity_type)
+                .or_insert_with(|| alias_lookup(aliases, &entity.entity_type))
+                .get(&tokens)
+                .copied();
+            if let Some(name) = canonical_name {
+                tokens = normalize(name, &entity.entity_type);
+            }
+
+            clusters
+                .entry((entity.entity_type.as_str(), tokens.clone()))
+                .or_insert_with(|| CanonicalEntity {
+                    entity_type: &entity.entity_type,
+                    tokens,
+                    canonical_name,
+                    mentions: Vec::new(),
+                })
+                .mentions
+                .push((entity, chunk));
+        }
+    }
+
+    let mut clusters: Vec<CanonicalEntity> = clusters.into_values().collect();
+
+    // Merge partial names such as "Smith" into "John Smith" when only one longer name fits
+    clusters.sort_by_key(|cluster| cluster.tokens.len());
+    let mut i = 0;
+    while i < clusters.len() {
+        let cluster = &clusters[i];
+        let supersets: Vec<usize> = (0..clusters.len())
+            .filter(|&j| {
+                j != i
+                    && PARTIAL_NAME_TYPES.contains(&cluster.entity_type)
+                    && clusters[j].entity_type == cluster.entity_type
+                    && clusters[j].tokens.len() > cluster.tokens.len()
+                    && clusters[j].tokens.is_superset(&cluster.tokens)
+            })
+            .collect();
+        let maximal: Vec<usize> = supersets
+            .iter()
+            .copied()
+            .filter(|&j| {
+                !supersets
+                    .iter()
+                    .any(|&k| k != j && clusters[k].tokens.is_superset(&clusters[j].tokens))
+            })
+            .collect();
+
+        if let [target] = maximal[..] {
+            let merged = clusters.remove(i);
+            let target = if target > i { target - 1 } else { target };
+            clusters[target].mentions.extend(merged.mentions);
+            if clusters[target].canonical_name.is_none() {
+                clusters[target].canonical_name = merged.canonical_name;
+            }
+        } else {
+            i += 1;
+        }
+    }
+
+    clusters
+}
+
+pub(crate) fn format_entities(
+    entities_chunks: &[Vec<EntityData>],
+    chunks: &[ChunkMetadata],
+    chunk_texts: &[&str],
+    aliases: &EntityAliases,
+    config: &ReportConfig,
+    locale: &Locale,
+) -> String {
+    let clusters = canonicalize(entities_chunks, aliases, config);
+
+    if clusters.is_empty() {
+        return locale.no_entities.to_string();
+    }
+
+    // Group by entity type
+    let mut entity_groups: BTreeMap<&str, Vec<&CanonicalEntity>> = BTreeMap::new();
+    for cluster in &clusters {
+        entity_groups
+            .entry(cluster.entity_type)
+            .or_default()
+            .push(cluster);
+    }
+
+    let mut sections = Vec::new();
+    for (entity_type, mut entities) in entity_groups {
+        // Most mentioned first, then by confidence
+        entities.sort_by(|a, b| {
+            b.mentions
+                .len()
+                .cmp(&a.mentions.len())
+                .then(b.confidence().total_cmp(&a.confidence()))
+                .then(a.display_name().cmp(b.display_name()))
+        });
+
+        let omitted = config
+            .max_entities_per_type
+            .map(|limit| entities.len().saturating_sub(limit))
+            .unwrap_or(0);
+        entities.truncate(entities.len() - omitted);
+
+        let rows: Vec<String> = entities
+            .iter()
+            .map(|entity| {
+                let name = entity.display_name();
+                let mut variants: Vec<(&str, usize)> = entity
+                    .variants()
+                    .into_iter()
+                    .filter(|(text, _)| *text != name)
+                    .collect();
+                variants.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(b.0)));
+
+                let location = |mention: Option<&(&Entity, usize)>| {
+                    mention
+                        .map(|&(entity, chunk)| {
+                            position_link(chunk, entity.begin_offset, chunks, locale)
+                        })
+                        .unwrap_or_default()
+                };
+                let context = entity
+                    .first_mention()
+                    .and_then(|&(entity, chunk)| {
+                        chunk_texts
+                            .get(chunk)
+                            .map(|text| context_snippet(text, entity))
+                    })
+                    .unwrap_or_default();
+
+                format!(
+                    "| {} | {} | {} | {} | {} | {:.1}% | {} |",
+                    table_cell(name),
+                    entity.mentions.len(),
+                    location(entity.first_mention()),
+                    location(entity.last_mention()),
+                    variants
+                        .iter()
+                        .map(|(text, _)| table_cell(text))
+                        .collect::<Vec<String>>()
+                        .join(", "),
+                    entity.confidence() * 100.0,
+                    context
+                )
+            })
+            .collect();
+
+        let mut section = format!(
+            "### {}\n| {} | {} | {} | {} | {} | {} | {} |\n|---|---|---|---|---|---|---|\n{}",
+            entity_type,
+            locale.entity,
+            locale.mentions,
+            locale.first,
+            locale.last,
+            locale.variants,
+            locale.confidence,
+            locale.context,
+            rows.join("\n")
+        );
+        if omitted > 0 {
+            section.push_str(&format!("\n\n_{}_", (locale.and_more)(omitted)));
+        }
+
+        sections.push(section);
+    }
+
+    sections.join("\n\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn entity(text: &str, entity_type: &str) -> Entity {
+        Entity {
+            text: text.to_string(),
+            entity_type: entity_type.to_string(),
+            score: 0.9,
+            begin_offset: 0,
+            end_offset: text.chars().count(),
+        }
+    }
+
+    fn chunks(entities: Vec<Entity>) -> Vec<Vec<EntityData>> {
+        vec![vec![EntityData { entities }]]
+    }
+
+    /// The display names and mention counts of the clusters, sorted by name.
+    fn clusters(entities: Vec<Entity>, aliases: &EntityAliases) -> Vec<(String, usize)> {
+        let chunks = chunks(entities);
+        let mut clusters: Vec<(String, usize)> =
+            canonicalize(&chunks, aliases, &ReportConfig::default())
+                .iter()
+                .map(|cluster| (cluster.display_name().to_string(), cluster.mentions.len()))
+                .collect();
+        clusters.sort();
+        clusters
+    }
+
+    fn set(tokens: &[&str]) -> BTreeSet<String> {
+        tokens.iter().map(|token| token.to_string()).collect()
+    }
+
+    #[test]
+    fn tokenize_drops_case_punctuation_and_possessives() {
+        assert_eq!(
+            tokenize("Acme's Q3-Launch!", false),
+            ["acme", "q3", "launch"]
+        );
+        assert_eq!(tokenize("Acme’s", false), ["acme"]);
+        assert_eq!(tokenize("  ", false), Vec::<String>::new());
+    }
+
+    #[test]
+    fn tokenize_singularizes_plurals() {
+        assert_eq!(tokenize("companies", true), ["company"]);
+        assert_eq!(tokenize("boxes", true), ["box"]);
+        assert_eq!(tokenize("branches wishes", true), ["branch", "wish"]);
+        assert_eq!(tokenize("classes", true), ["class"]);
+        assert_eq!(tokenize("widgets", true), ["widget"]);
+        // Words ending in "ss" and short words are left alone
+        assert_eq!(tokenize("business", true), ["business"]);
+        assert_eq!(tokenize("bus gas", true), ["bus", "gas"]);
+        assert_eq!(tokenize("widgets", false), ["widgets"]);
+    }
+
+    #[test]
+    fn normalize_strips_honorifics_from_people_only() {
+        assert_eq!(
+            normalize("Dr. Jane Smith", "PERSON"),
+            set(&["jane", "smith"])
+        );
+        assert_eq!(normalize("Prof Smith", "PERSON"), set(&["smith"]));
+        assert_eq!(normalize("Dr", "PERSON"), set(&["dr"]));
+        assert_eq!(normalize("Ms Marvel", "TITLE"), set(&["ms", "marvel"]));
+        // People keep their trailing s
+        assert_eq!(normalize("James", "PERSON"), set(&["james"]));
+        assert_eq!(normalize("Widgets", "COMMERCIAL_ITEM"), set(&["widget"]));
+    }
+
+    #[test]
+    fn spellings_of_one_name_are_clustered() {
+        let aliases = EntityAliases::new();
+        let found = clusters(
+            vec![
+                entity("Dr. Jane Smith", "PERSON"),
+                entity("jane smith", "PERSON"),
+                entity("Jane Smith's", "PERSON"),
+            ],
+            &aliases,
+        );
+        assert_eq!(found, [("Dr. Jane Smith".to_string(), 3)]);
+    }
+
+    #[test]
+    fn partial_names_merge_into_a_unique_longer_name() {
+        let aliases = EntityAliases::new();
+        let found = clusters(
+            vec![
+                entity("John Smith", "PERSON"),
+                entity("Smith", "PERSON"),
+                entity("John", "PERSON"),
+            ],
+            &aliases,
+        );
+        assert_eq!(found, [("John Smith".to_string(), 3)]);
+    }
+
+    #[test]
+    fn partial_names_merge_through_nested_names_to_the_longest() {
+        let aliases = EntityAliases::new();
+        let found = clusters(
+            vec![
+                entity("John Q Smith", "PERSON"),
+                entity("John Smith", "PERSON"),
+                entity("Smith", "PERSON"),
+            ],
+            &aliases,
+        );
+        assert_eq!(found, [("John Q Smith".to_string(), 3)]);
+    }
+
+    #[test]
+    fn ambiguous_partial_names_stay_apart() {
+        let aliases = EntityAliases::new();
+        let found = clusters(
+            vec![
+                entity("John Smith", "PERSON"),
+                entity("Jane Smith", "PERSON"),
+                entity("Smith", "PERSON"),
+            ],
+            &aliases,
+        );
+        assert_eq!(
+            found,
+            [
+                ("Jane Smith".to_string(), 1),
+                ("John Smith".to_string(), 1),
+                ("Smith".to_string(), 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn partial_names_only_merge_within_name_types() {
+        let aliases = EntityAliases::new();
+        let found = clusters(
+            vec![
+                entity("Summer Launch", "EVENT"),
+                entity("Launch", "EVENT"),
+                entity("Smith", "ORGANIZATION"),
+                entity("John Smith", "PERSON"),
+            ],
+            &aliases,
+        );
+        assert_eq!(found.len(), 4);
+    }
+
+    #[test]
+    fn aliases_resolve_to_their_canonical_name() {
+        let aliases = EntityAliases::from([
+            (
+                "Amazon Web Services".to_string(),
+                vec!["AWS".to_string(), "Amazon's cloud".to_string()],
+            ),
+            ("Acme".to_string(), vec!["ACME Corp".to_string()]),
+        ]);
+        let found = clusters(
+            vec![
+                entity("aws", "ORGANIZATION"),
+                entity("Amazon cloud", "ORGANIZATION"),
+                entity("amazon web services", "ORGANIZATION"),
+                entity("Acme Corp", "ORGANIZATION"),
+            ],
+            &aliases,
+        );
+        assert_eq!(
+            found,
+            [
+                ("Acme".to_string(), 1),
+                ("Amazon Web Services".to_string(), 3)
+            ]
+        );
+    }
+
+    #[test]
+    fn alias_lookup_keeps_the_first_canonical_name_for_shared_aliases() {
+        let aliases = EntityAliases::from([
+            ("Beta".to_string(), vec!["B".to_string()]),
+            ("Alpha".to_string(), vec!["B".to_string()]),
+        ]);
+        let lookup = alias_lookup(&aliases, "ORGANIZATION");
+        assert_eq!(lookup[&set(&["b"])], "Alpha");
+        assert_eq!(lookup[&set(&["beta"])], "Beta");
+        assert_eq!(lookup.len(), 3);
+    }
+
+    #[test]
+    fn snippets_bold_the_mention_between_whole_words() {
+        let text = "We met with the team from Acme to plan the launch.";
+        let mention = Entity {
+            begin_offset: 26,
+            end_offset: 30,
+            ..entity("Acme", "ORGANIZATION")
+        };
+        assert_eq!(
+            context_snippet(text, &mention),
+            "We met with the team from **Acme** to plan the launch."
+        );
+    }
+
+    #[test]
+    fn snippets_are_trimmed_by_characters_at_word_boundaries() {
+        let text = format!("{} café Zoë {}", "données ".repeat(10), "über ".repeat(20));
+        let begin = text.chars().position(|c| c == 'Z').unwrap();
+        let mention = Entity {
+            begin_offset: begin,
+            end_offset: begin + 3,
+            ..entity("Zoë", "PERSON")
+        };
+
+        let snippet = context_snippet(&text, &mention);
+        assert!(snippet.starts_with("…données "), "{}", snippet);
+        assert!(snippet.contains("café **Zoë** über"), "{}", snippet);
+        assert!(snippet.ends_with(" über…"), "{}", snippet);
+    }
+
+    #[test]
+    fn snippets_with_bad_offsets_are_empty() {
+        let mention = Entity {
+            begin_offset: 5,
+            end_offset: 50,
+            ..entity("Acme", "ORGANIZATION")
+        };
+        assert_eq!(context_snippet("Acme Corp", &mention), "");
+        let empty = Entity {
+            begin_offset: 2,
+            end_offset: 2,
+            ..entity("Acme", "ORGANIZATION")
+        };
+        assert_eq!(context_snippet("Acme Corp", &empty), "");
+    }
+
+    #[test]
+    fn snippets_are_safe_in_table_cells() {
+        let text = "Costs | Acme\nnext line";
+        let mention = Entity {
+            begin_offset: 8,
+            end_offset: 12,
+            ..entity("Acme", "ORGANIZATION")
+        };
+        assert_eq!(
+            context_snippet(text, &mention),
+            "Costs \\| **Acme** next line"
+        );
+    }
+}
@@ -0,0 +1,187 @@
+//! Renders the Step Functions payloads in `tests/fixtures` and compares every artifact with the
+//! golden files in `tests/golden`. After an intended change to the output, rerun with
+//! `UPDATE_GOLDEN=1` and review the diff of the golden files.
+//!
+//! `interview.json` comes from the execution that produced `assets/interview-report.md`: its
+//! chunks are `assets/interview-transcript.md` as the pipeline split it, and its model replies
+//! and entities are the ones in that report. The `standup` fixtures are written by hand to reach
+//! the sections and warnings the interview doesn't, with the prompt hashes of the pipeline
+//! version they name.
+
+use super::compile_report;
+use chrono::{TimeZone, Utc};
+use serde_json::Value;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+const FIXTURES: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures");
+const GOLDEN: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/golden");
+const RECORDED_REPORT: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/../../assets/interview-report.md"
+);
+
+fn fixtures() -> Vec<PathBuf> {
+    let mut paths: Vec<PathBuf> = fs::read_dir(FIXTURES)
+        .expect("fixtures directory")
+        .map(|entry| entry.expect("fixture entry").path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+        .collect();
+    paths.sort();
+    paths
+}
+
+/// Compares one artifact with its golden file, or rewrites the file when updating. Artifacts
+/// that weren't produced must not have a golden file.
+fn check(path: &Path, actual: Option<&str>, update: bool) -> bool {
+    if update {
+        match actual {
+            Some(actual) => {
+                fs::create_dir_all(path.parent().expect("golden directory")).unwrap();
+                fs::write(path, actual).unwrap();
+            }
+            None if path.exists() => fs::remove_file(path).unwrap(),
+            None => {}
+        }
+        return true;
+    }
+
+    fs::read_to_string(path).ok().as_deref() == actual
+}
+
+#[test]
+fn reports_match_golden_files() {
+    let generated_at = Utc.with_ymd_and_hms(2024, 12, 6, 1, 32, 24).unwrap();
+    let update = env::var_os("UPDATE_GOLDEN").is_some();
+    let mut mismatches = Vec::new();
+
+    let fixtures = fixtures();
+    assert!(!fixtures.is_empty(), "no fixtures in {}", FIXTURES);
+
+    for fixture in fixtures {
+        let name = fixture.file_stem().unwrap().to_string_lossy().to_string();
+        let payload: Value = serde_json::from_str(&fs::read_to_string(&fixture).unwrap())
+            .unwrap_or_else(|e| panic!("{} is not valid JSON: {}", fixture.display(), e));
+        let response = compile_report(payload, generated_at)
+            .unwrap_or_else(|e| panic!("{} failed to compile: {}", name, e));
+
+        // Everything but the documents themselves, such as provenance and warnings
+        let mut metadata = serde_json::to_value(&response).unwrap();
+        for field in ["body", "appendix", "mind_map_svg"] {
+            metadata.as_object_mut().unwrap().remove(field);
+        }
+        let metadata = format!("{}\n", serde_json::to_string_pretty(&metadata).unwrap());
+
+        let dir = Path::new(GOLDEN).join(&name);
+        let artifacts = [
+            ("report.md", Some(response.body.as_str())),
+            ("appendix.md", response.appendix.as_deref()),
+            ("mindmap.svg", response.mind_map_svg.as_deref()),
+            ("response.json", Some(metadata.as_str())),
+        ];
+        for (file, actual) in artifacts {
+            let path = dir.join(file);
+            if !check(&path, actual, update) {
+                mismatches.push(path.display().to_string());
+            }
+        }
+    }
+
+    assert!(
+        mismatches.is_empty(),
+        "output differs from the golden files, rerun with UPDATE_GOLDEN=1 if the change is intended:\n{}",
+        mismatches.join("\n")
+    );
+}
+
+/// Reverses the order Comprehend listed each chunk's entities and key phrases in.
+fn reverse_comprehend_results(payload: &mut Value) {
+    for (field, list) in [("entities", "Entities"), ("key_phrases", "KeyPhrases")] {
+        let Some(chunks) = payload.get_mut(field).and_then(Value::as_array_mut) else {
+            continue;
+        };
+        for result in chunks
+            .iter_mut()
+            .flat_map(|chunk| chunk.as_array_mut().unwrap())
+        {
+            if let Some(items) = result.get_mut(list).and_then(Value::as_array_mut) {
+                items.reverse();
+            }
+        }
+    }
+}
+
+#[test]
+fn reports_do_not_depend_on_comprehend_result_order() {
+    let generated_at = Utc.with_ymd_and_hms(2024, 12, 6, 1, 32, 24).unwrap();
+
+    for fixture in fixtures() {
+        let payload: Value = serde_json::from_str(&fs::read_to_string(&fixture).unwrap()).unwrap();
+        let mut reversed = payload.clone();
+        reverse_comprehend_results(&mut reversed);
+
+        let first = compile_report(payload, generated_at).unwrap();
+        let second = compile_report(reversed, generated_at).unwrap();
+
+        assert_eq!(
+            serde_json::to_value(&first).unwrap(),
+            serde_json::to_value(&second).unwrap(),
+            "{} changed when Comprehend listed its results in another order",
+            fixture.display()
+        );
+    }
+}
+
+/// A markdown section up to the next heading of the same level.
+fn section<'a>(markdown: &'a str, heading: &str) -> &'a str {
+    let level = heading.len() - heading.trim_start_matches('#').len();
+    let start = markdown
+        .find(&format!("{}\n", heading))
+        .unwrap_or_else(|| panic!("no {} section", heading));
+    let body = &markdown[start + heading.len()..];
+    let end = body
+        .find(&format!("\n{} ", "#".repeat(level)))
+        .unwrap_or(body.len());
+    &body[..end]
+}
+
+/// Evens out the line wrapping of the recorded report.
+fn words(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<&str>>().join(" ")
+}
+
+#[test]
+fn recorded_interview_keeps_its_model_output() {
+    let generated_at = Utc.with_ymd_and_hms(2024, 12, 6, 1, 32, 24).unwrap();
+    let payload: Value = serde_json::from_str(
+        &fs::read_to_string(Path::new(FIXTURES).join("interview.json")).unwrap(),
+    )
+    .unwrap();
+    let report = compile_report(payload, generated_at).unwrap().body;
+    let recorded = fs::read_to_string(RECORDED_REPORT).unwrap();
+
+    for heading in ["## Overview", "## Main Topics"] {
+        assert_eq!(
+            words(section(&report, heading)),
+            words(section(&recorded, heading)),
+            "{} differs from the recorded report",
+            heading
+        );
+    }
+
+    let entities = section(&report, "## Named Entities");
+    for line in section(&recorded, "## Named Entities").lines() {
+        let Some((name, _)) = line
+            .strip_prefix("- ")
+            .and_then(|item| item.split_once(" (confidence"))
+        else {
+            continue;
+        };
+        assert!(
+            entities.contains(name),
+            "{} is missing from the named entities",
+            name
+        );
+    }
+}
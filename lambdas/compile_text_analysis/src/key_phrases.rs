@@ -0,0 +1,177 @@
+use crate::config::ReportConfig;
+use crate::entities::tokenize;
+use crate::locale::Locale;
+use crate::transcript::{chunk_link, table_cell, ChunkMetadata};
+use serde::Deserialize;
+use std::cmp::Reverse;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Leading words that don't change what a phrase refers to.
+const DETERMINERS: [&str; 13] = [
+    "a", "an", "the", "this", "that", "these", "those", "our", "my", "your", "their", "his", "her",
+];
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct KeyPhrase {
+    pub(crate) text: String,
+    pub(crate) score: f64,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct KeyPhraseData {
+    pub(crate) key_phrases: Vec<KeyPhrase>,
+}
+
+/// Every mention of one phrase across the transcript.
+#[derive(Default)]
+struct RankedPhrase<'a> {
+    mentions: Vec<(&'a KeyPhrase, usize)>,
+}
+
+impl RankedPhrase<'_> {
+    fn mean_score(&self) -> f64 {
+        self.mentions.iter().map(|(p, _)| p.score).sum::<f64>() / self.mentions.len() as f64
+    }
+
+    /// The most common spelling, preferring the earliest on ties.
+    fn display_text(&self) -> &str {
+        let mut counts: BTreeMap<&str, usize> = BTreeMap::new();
+        for (phrase, _) in &self.mentions {
+            *counts.entry(phrase.text.trim()).or_default() += 1;
+        }
+
+        self.mentions
+            .iter()
+            .map(|(phrase, _)| phrase.text.trim())
+            // The first of equal minimums, so the earliest spelling wins ties
+            .min_by_key(|text| Reverse(counts[text]))
+            .unwrap_or_default()
+    }
+
+    fn chunks(&self) -> BTreeSet<usize> {
+        self.mentions.iter().map(|&(_, chunk)| chunk).collect()
+    }
+}
+
+/// Lowercased, singularized tokens with leading determiners removed.
+fn phrase_key(text: &str) -> String {
+    let tokens = tokenize(text, true);
+    let start = tokens
+        .iter()
+        .take_while(|token| DETERMINERS.contains(&token.as_str()))
+        .count();
+
+    tokens[start..].join(" ")
+}
+
+pub(crate) fn format_key_phrases(
+    key_phrase_chunks: &[Vec<KeyPhraseData>],
+    chunks: &[ChunkMetadata],
+    config: &ReportConfig,
+    locale: &Locale,
+) -> String {
+    // Comprehend results arrive in chunk order once flattened
+    let mut phrases: BTreeMap<String, RankedPhrase> = BTreeMap::new();
+    for (chunk, data) in key_phrase_chunks.iter().flatten().enumerate() {
+        for phrase in data
+            .key_phrases
+            .iter()
+            .filter(|phrase| phrase.score >= config.min_key_phrase_score)
+        {
+            let key = phrase_key(&phrase.text);
+            if !key.is_empty() {
+                phrases
+                    .entry(key)
+                    .or_default()
+                    .mentions
+                    .push((phrase, chunk));
+            }
+        }
+    }
+
+    if phrases.is_empty() {
+        return locale.no_key_phrases.to_string();
+    }
+
+    // Most frequent first, then by average score
+    let mut ranked: Vec<RankedPhrase> = phrases.into_values().collect();
+    ranked.sort_by(|a, b| {
+        b.mentions
+            .len()
+            .cmp(&a.mentions.len())
+            .then(b.mean_score().total_cmp(&a.mean_score()))
+            .then(a.display_text().cmp(b.display_text()))
+    });
+    ranked.truncate(config.max_key_phrases);
+
+    let rows: Vec<String> = ranked
+        .iter()
+        .map(|phrase| {
+            format!(
+                "| {} | {} | {} | {:.1}% |",
+                table_cell(phrase.display_text()),
+                phrase.mentions.len(),
+                phrase
+                    .chunks()
+                    .into_iter()
+                    .map(|chunk| chunk_link(chunk, chunks, locale))
+                    .collect::<Vec<String>>()
+                    .join(", "),
+                phrase.mean_score() * 100.0
+            )
+        })
+        .collect();
+
+    format!(
+        "| {} | {} | {} | {} |\n|---|---|---|---|\n{}",
+        locale.phrase,
+        locale.mentions,
+        locale.location,
+        locale.score,
+        rows.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn phrase(text: &str) -> KeyPhrase {
+        KeyPhrase {
+            text: text.to_string(),
+            score: 0.9,
+        }
+    }
+
+    fn ranked<'a>(phrases: &'a [KeyPhrase]) -> RankedPhrase<'a> {
+        RankedPhrase {
+            mentions: phrases.iter().enumerate().map(|(i, p)| (p, i)).collect(),
+        }
+    }
+
+    #[test]
+    fn display_text_prefers_the_most_common_spelling() {
+        let phrases = [
+            phrase("Q3 Launch"),
+            phrase("Q3 launch"),
+            phrase("Q3 launch"),
+        ];
+        assert_eq!(ranked(&phrases).display_text(), "Q3 launch");
+    }
+
+    #[test]
+    fn display_text_prefers_the_earliest_spelling_on_ties() {
+        let phrases = [
+            phrase("Q3 Launch"),
+            phrase("Q3 launch"),
+            phrase("Q3 launch"),
+            phrase("Q3 Launch"),
+        ];
+        assert_eq!(ranked(&phrases).display_text(), "Q3 Launch");
+
+        let reversed = [phrase("the budget"), phrase("The budget")];
+        assert_eq!(ranked(&reversed).display_text(), "the budget");
+    }
+}
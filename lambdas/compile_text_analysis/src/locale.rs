@@ -0,0 +1,913 @@
+/// Every heading and label in the report, in one language.
+pub(crate) struct Locale {
+    pub(crate) language: &'static str,
+
+    pub(crate) title: &'static str,
+    pub(crate) generated_on: &'static str,
+    pub(crate) overview: &'static str,
+    pub(crate) main_topics: &'static str,
+    pub(crate) topic_map: &'static str,
+    /// Groups chunk topics that don't match any of the main topics.
+    pub(crate) other_topics: &'static str,
+    pub(crate) action_items: &'static str,
+    pub(crate) decisions: &'static str,
+    pub(crate) open_questions: &'static str,
+    pub(crate) key_phrases: &'static str,
+    pub(crate) sentiment_analysis: &'static str,
+    pub(crate) speaker_analytics: &'static str,
+    pub(crate) named_entities: &'static str,
+    pub(crate) chunk_summaries: &'static str,
+    pub(crate) chunk_summary: fn(usize) -> String,
+    pub(crate) topics: &'static str,
+    /// Points at the separate file holding the chunk summaries.
+    pub(crate) appendix_note: fn(&str) -> String,
+    pub(crate) back_to_report: &'static str,
+    pub(crate) timeline: &'static str,
+    pub(crate) comprehend_fallback: fn(&str) -> String,
+    pub(crate) unknown_citations: fn(&str) -> String,
+
+    pub(crate) quality_warnings: &'static str,
+    pub(crate) empty_transcript: &'static str,
+    pub(crate) empty_chunk: fn(&str) -> String,
+    /// Names a model output that stopped at the token limit.
+    pub(crate) truncated: fn(&str) -> String,
+    pub(crate) unreadable_outcomes: fn(&str) -> String,
+    /// Names an analysis and how many of the chunks it has no results for.
+    pub(crate) missing_analysis: fn(&str, usize, usize) -> String,
+    pub(crate) low_confidence: fn(&str, &str) -> String,
+
+    /// Labels a link to a chunk whose start time isn't known.
+    pub(crate) chunk_label: fn(usize) -> String,
+    pub(crate) transcript_offset: &'static str,
+    pub(crate) at_time: fn(&str) -> String,
+    pub(crate) in_chunk: fn(&str) -> String,
+
+    pub(crate) no_action_items: &'static str,
+    pub(crate) no_decisions: &'static str,
+    pub(crate) no_open_questions: &'static str,
+    pub(crate) owner: &'static str,
+    pub(crate) task: &'static str,
+    pub(crate) due: &'static str,
+    pub(crate) source: &'static str,
+
+    pub(crate) no_key_phrases: &'static str,
+    pub(crate) phrase: &'static str,
+    pub(crate) mentions: &'static str,
+    pub(crate) location: &'static str,
+    pub(crate) score: &'static str,
+
+    pub(crate) no_entities: &'static str,
+    pub(crate) entity: &'static str,
+    pub(crate) first: &'static str,
+    pub(crate) last: &'static str,
+    pub(crate) variants: &'static str,
+    pub(crate) confidence: &'static str,
+    pub(crate) context: &'static str,
+    pub(crate) and_more: fn(usize) -> String,
+
+    /// Positive, negative, neutral and mixed, in that order.
+    pub(crate) sentiment_labels: [&'static str; 4],
+    pub(crate) overall_sentiment: &'static str,
+    pub(crate) aggregation: &'static str,
+    pub(crate) confidence_scores: &'static str,
+    pub(crate) arc: &'static str,
+    pub(crate) arc_legend: &'static str,
+    pub(crate) no_sentiment: &'static str,
+    pub(crate) chunk: &'static str,
+    pub(crate) time: &'static str,
+    pub(crate) sentiment: &'static str,
+    pub(crate) transitions: &'static str,
+    pub(crate) turned: fn(&str, &str) -> String,
+    pub(crate) stayed: fn(&str) -> String,
+    pub(crate) majority: &'static str,
+    pub(crate) length_weighted: &'static str,
+    pub(crate) confidence_weighted: &'static str,
+    pub(crate) across_chunks: fn(usize) -> String,
+    pub(crate) lengths_unavailable: &'static str,
+
+    pub(crate) no_speakers: &'static str,
+    pub(crate) speaker_name: fn(usize) -> String,
+    pub(crate) speaker_summary: fn(usize, &str, usize) -> String,
+    pub(crate) speaker: &'static str,
+    pub(crate) talk_time: &'static str,
+    pub(crate) share: &'static str,
+    pub(crate) turns: &'static str,
+    pub(crate) average_turn: &'static str,
+    pub(crate) longest_monologue: &'static str,
+    pub(crate) interruptions: &'static str,
+    pub(crate) rate: &'static str,
+    pub(crate) words_per_minute: &'static str,
+}
+
+impl Locale {
+    /// Picks the catalog for a Transcribe locale such as `es-US`, falling back to English.
+    pub(crate) fn for_language(language_code: Option<&str>) -> &'static Locale {
+        let language = language_code
+            .and_then(|code| code.split(['-', '_']).next())
+            .unwrap_or_default();
+
+        LOCALES
+            .iter()
+            .find(|locale| locale.language.eq_ignore_ascii_case(language))
+            .copied()
+            .unwrap_or(&EN)
+    }
+
+    /// The lowercase label for a Comprehend sentiment such as `POSITIVE`.
+    pub(crate) fn sentiment_label(&self, sentiment: &str) -> String {
+        let index = match sentiment {
+            "POSITIVE" => 0,
+            "NEGATIVE" => 1,
+            "NEUTRAL" => 2,
+            "MIXED" => 3,
+            _ => return sentiment.to_lowercase(),
+        };
+        self.sentiment_labels[index].to_lowercase()
+    }
+}
+
+static LOCALES: [&Locale; 6] = [&EN, &ES, &FR, &DE, &PT, &IT];
+
+static EN: Locale = Locale {
+    language: "en",
+
+    title: "Analysis Results for",
+    generated_on: "Generated on",
+    overview: "Overview",
+    main_topics: "Main Topics",
+    topic_map: "Topic Map",
+    other_topics: "Other",
+    action_items: "Action Items",
+    decisions: "Decisions",
+    open_questions: "Open Questions",
+    key_phrases: "Key Phrases",
+    sentiment_analysis: "Sentiment Analysis",
+    speaker_analytics: "Speaker Analytics",
+    named_entities: "Named Entities",
+    chunk_summaries: "Detailed Section Summaries",
+    chunk_summary: |n| format!("Chunk {} Summary", n),
+    topics: "Topics",
+    appendix_note: |target| format!("Each chunk's summary is in the [appendix](<{}>).", target),
+    back_to_report: "Back to the report",
+    timeline: "Timeline",
+    comprehend_fallback: |language| {
+        format!(
+            "Comprehend does not support {}; entities, key phrases and sentiment were analyzed as English.",
+            language
+        )
+    },
+    unknown_citations: |sections| {
+        format!(
+            "The overview cites sections that don't exist: {}.",
+            sections
+        )
+    },
+
+    quality_warnings: "Quality Warnings",
+    empty_transcript: "No speech was transcribed.",
+    empty_chunk: |chunk| format!("The text of {} is empty.", chunk),
+    truncated: |output| format!("Cut off at the model's token limit: {}.", output),
+    unreadable_outcomes: |chunk| {
+        format!(
+            "The action items, decisions and open questions of {} could not be read.",
+            chunk
+        )
+    },
+    missing_analysis: |analysis, missing, total| {
+        format!(
+            "{}: no results for {} of {} chunks.",
+            analysis, missing, total
+        )
+    },
+    low_confidence: |chunk, confidence| {
+        format!("Transcript confidence in {} is only {}.", chunk, confidence)
+    },
+
+    chunk_label: |n| format!("chunk {}", n),
+    transcript_offset: "transcript offset",
+    at_time: |time| format!("at {}", time),
+    in_chunk: |link| format!("in {}", link),
+
+    no_action_items: "No action items identified",
+    no_decisions: "No decisions identified",
+    no_open_questions: "No open questions identified",
+    owner: "Owner",
+    task: "Task",
+    due: "Due",
+    source: "Source",
+
+    no_key_phrases: "No key phrases detected",
+    phrase: "Phrase",
+    mentions: "Mentions",
+    location: "Where",
+    score: "Score",
+
+    no_entities: "No entities detected",
+    entity: "Entity",
+    first: "First",
+    last: "Last",
+    variants: "Variants",
+    confidence: "Confidence",
+    context: "Context",
+    and_more: |n| format!("…and {} more", n),
+
+    sentiment_labels: ["Positive", "Negative", "Neutral", "Mixed"],
+    overall_sentiment: "Overall sentiment",
+    aggregation: "Aggregation",
+    confidence_scores: "Confidence Scores",
+    arc: "Arc",
+    arc_legend: "net positive − negative per chunk",
+    no_sentiment: "No sentiment data available",
+    chunk: "Chunk",
+    time: "Time",
+    sentiment: "Sentiment",
+    transitions: "Transitions",
+    turned: |label, position| format!("Turned {} {}", label, position),
+    stayed: |label| format!("Sentiment stayed {} throughout.", label),
+    majority: "majority vote, chunks counted equally",
+    length_weighted: "weighted by chunk length",
+    confidence_weighted: "weighted by chunk length and winning-label confidence",
+    across_chunks: |n| format!("across {} chunk{}", n, if n == 1 { "" } else { "s" }),
+    lengths_unavailable: "chunk lengths unavailable, counted equally",
+
+    no_speakers: "No speaker labels available",
+    speaker_name: |n| format!("Speaker {}", n),
+    speaker_summary: |speakers, duration, interruptions| {
+        format!(
+            "{} speaker{} over {} of speech, {} interruption{}.",
+            speakers,
+            if speakers == 1 { "" } else { "s" },
+            duration,
+            interruptions,
+            if interruptions == 1 { "" } else { "s" }
+        )
+    },
+    speaker: "Speaker",
+    talk_time: "Talk Time",
+    share: "Share",
+    turns: "Turns",
+    average_turn: "Avg Turn",
+    longest_monologue: "Longest Monologue",
+    interruptions: "Interruptions",
+    rate: "Rate",
+    words_per_minute: "wpm",
+};
+
+static ES: Locale = Locale {
+    language: "es",
+
+    title: "Resultados del análisis de",
+    generated_on: "Generado el",
+    overview: "Resumen general",
+    main_topics: "Temas principales",
+    topic_map: "Mapa de temas",
+    other_topics: "Otros",
+    action_items: "Tareas pendientes",
+    decisions: "Decisiones",
+    open_questions: "Preguntas abiertas",
+    key_phrases: "Frases clave",
+    sentiment_analysis: "Análisis de sentimiento",
+    speaker_analytics: "Análisis de hablantes",
+    named_entities: "Entidades nombradas",
+    chunk_summaries: "Resúmenes detallados por sección",
+    chunk_summary: |n| format!("Resumen del fragmento {}", n),
+    topics: "Temas",
+    appendix_note: |target| {
+        format!(
+            "El resumen de cada fragmento está en el [apéndice](<{}>).",
+            target
+        )
+    },
+    back_to_report: "Volver al informe",
+    timeline: "Cronología",
+    comprehend_fallback: |language| {
+        format!(
+            "Comprehend no admite {}; las entidades, las frases clave y el sentimiento se analizaron como inglés.",
+            language
+        )
+    },
+    unknown_citations: |sections| {
+        format!(
+            "El resumen general cita secciones que no existen: {}.",
+            sections
+        )
+    },
+
+    quality_warnings: "Advertencias de calidad",
+    empty_transcript: "No se transcribió ninguna intervención.",
+    empty_chunk: |chunk| format!("El texto del {} está vacío.", chunk),
+    truncated: |output| format!("Cortado por el límite de tokens del modelo: {}.", output),
+    unreadable_outcomes: |chunk| {
+        format!(
+            "No se pudieron leer las tareas pendientes, decisiones y preguntas abiertas del {}.",
+            chunk
+        )
+    },
+    missing_analysis: |analysis, missing, total| {
+        format!(
+            "{}: sin resultados para {} de {} fragmentos.",
+            analysis, missing, total
+        )
+    },
+    low_confidence: |chunk, confidence| {
+        format!(
+            "La confianza de la transcripción en el {} es solo del {}.",
+            chunk, confidence
+        )
+    },
+
+    chunk_label: |n| format!("fragmento {}", n),
+    transcript_offset: "posición en la transcripción",
+    at_time: |time| format!("en {}", time),
+    in_chunk: |link| format!("en {}", link),
+
+    no_action_items: "No se identificaron tareas pendientes",
+    no_decisions: "No se identificaron decisiones",
+    no_open_questions: "No se identificaron preguntas abiertas",
+    owner: "Responsable",
+    task: "Tarea",
+    due: "Fecha límite",
+    source: "Origen",
+
+    no_key_phrases: "No se detectaron frases clave",
+    phrase: "Frase",
+    mentions: "Menciones",
+    location: "Dónde",
+    score: "Puntuación",
+
+    no_entities: "No se detectaron entidades",
+    entity: "Entidad",
+    first: "Primera",
+    last: "Última",
+    variants: "Variantes",
+    confidence: "Confianza",
+    context: "Contexto",
+    and_more: |n| format!("…y {} más", n),
+
+    sentiment_labels: ["Positivo", "Negativo", "Neutral", "Mixto"],
+    overall_sentiment: "Sentimiento general",
+    aggregation: "Agregación",
+    confidence_scores: "Puntuaciones de confianza",
+    arc: "Arco",
+    arc_legend: "positivo neto − negativo por fragmento",
+    no_sentiment: "No hay datos de sentimiento disponibles",
+    chunk: "Fragmento",
+    time: "Tiempo",
+    sentiment: "Sentimiento",
+    transitions: "Transiciones",
+    turned: |label, position| format!("Pasó a {} {}", label, position),
+    stayed: |label| format!("El sentimiento se mantuvo {} en todo momento.", label),
+    majority: "voto por mayoría, fragmentos con el mismo peso",
+    length_weighted: "ponderado por la longitud del fragmento",
+    confidence_weighted:
+        "ponderado por la longitud del fragmento y la confianza de la etiqueta ganadora",
+    across_chunks: |n| format!("en {} fragmento{}", n, if n == 1 { "" } else { "s" }),
+    lengths_unavailable: "longitudes de fragmento no disponibles, contados por igual",
+
+    no_speakers: "No hay etiquetas de hablante disponibles",
+    speaker_name: |n| format!("Hablante {}", n),
+    speaker_summary: |speakers, duration, interruptions| {
+        format!(
+            "{} hablante{} en {} de conversación, {} interrupci{}.",
+            speakers,
+            if speakers == 1 { "" } else { "s" },
+            duration,
+            interruptions,
+            if interruptions == 1 { "ón" } else { "ones" }
+        )
+    },
+    speaker: "Hablante",
+    talk_time: "Tiempo de habla",
+    share: "Proporción",
+    turns: "Turnos",
+    average_turn: "Turno medio",
+    longest_monologue: "Monólogo más largo",
+    interruptions: "Interrupciones",
+    rate: "Ritmo",
+    words_per_minute: "ppm",
+};
+
+static FR: Locale = Locale {
+    language: "fr",
+
+    title: "Résultats de l'analyse de",
+    generated_on: "Généré le",
+    overview: "Vue d'ensemble",
+    main_topics: "Sujets principaux",
+    topic_map: "Carte des sujets",
+    other_topics: "Autres",
+    action_items: "Actions à mener",
+    decisions: "Décisions",
+    open_questions: "Questions ouvertes",
+    key_phrases: "Expressions clés",
+    sentiment_analysis: "Analyse des sentiments",
+    speaker_analytics: "Analyse des intervenants",
+    named_entities: "Entités nommées",
+    chunk_summaries: "Résumés détaillés par section",
+    chunk_summary: |n| format!("Résumé du segment {}", n),
+    topics: "Sujets",
+    appendix_note: |target| {
+        format!(
+            "Le résumé de chaque segment se trouve dans l'[annexe](<{}>).",
+            target
+        )
+    },
+    back_to_report: "Retour au rapport",
+    timeline: "Chronologie",
+    comprehend_fallback: |language| {
+        format!(
+            "Comprehend ne prend pas en charge {} ; les entités, expressions clés et sentiments ont été analysés en anglais.",
+            language
+        )
+    },
+    unknown_citations: |sections| {
+        format!(
+            "La vue d'ensemble cite des sections qui n'existent pas : {}.",
+            sections
+        )
+    },
+
+    quality_warnings: "Avertissements de qualité",
+    empty_transcript: "Aucune parole n'a été transcrite.",
+    empty_chunk: |chunk| format!("Le texte du {} est vide.", chunk),
+    truncated: |output| format!("Coupé à la limite de jetons du modèle : {}.", output),
+    unreadable_outcomes: |chunk| {
+        format!(
+            "Les actions, décisions et questions ouvertes du {} n'ont pas pu être lues.",
+            chunk
+        )
+    },
+    missing_analysis: |analysis, missing, total| {
+        format!(
+            "{} : aucun résultat pour {} segments sur {}.",
+            analysis, missing, total
+        )
+    },
+    low_confidence: |chunk, confidence| {
+        format!(
+            "La confiance de la transcription dans le {} n'est que de {}.",
+            chunk, confidence
+        )
+    },
+
+    chunk_label: |n| format!("segment {}", n),
+    transcript_offset: "position dans la transcription",
+    at_time: |time| format!("à {}", time),
+    in_chunk: |link| format!("dans le {}", link),
+
+    no_action_items: "Aucune action identifiée",
+    no_decisions: "Aucune décision identifiée",
+    no_open_questions: "Aucune question ouverte identifiée",
+    owner: "Responsable",
+    task: "Tâche",
+    due: "Échéance",
+    source: "Source",
+
+    no_key_phrases: "Aucune expression clé détectée",
+    phrase: "Expression",
+    mentions: "Mentions",
+    location: "Où",
+    score: "Score",
+
+    no_entities: "Aucune entité détectée",
+    entity: "Entité",
+    first: "Première",
+    last: "Dernière",
+    variants: "Variantes",
+    confidence: "Confiance",
+    context: "Contexte",
+    and_more: |n| format!("…et {} de plus", n),
+
+    sentiment_labels: ["Positif", "Négatif", "Neutre", "Mitigé"],
+    overall_sentiment: "Sentiment global",
+    aggregation: "Agrégation",
+    confidence_scores: "Scores de confiance",
+    arc: "Arc",
+    arc_legend: "positif net − négatif par segment",
+    no_sentiment: "Aucune donnée de sentiment disponible",
+    chunk: "Segment",
+    time: "Temps",
+    sentiment: "Sentiment",
+    transitions: "Transitions",
+    turned: |label, position| format!("Devenu {} {}", label, position),
+    stayed: |label| format!("Le sentiment est resté {} tout du long.", label),
+    majority: "vote majoritaire, segments comptés à égalité",
+    length_weighted: "pondéré par la longueur des segments",
+    confidence_weighted:
+        "pondéré par la longueur des segments et la confiance de l'étiquette gagnante",
+    across_chunks: |n| format!("sur {} segment{}", n, if n == 1 { "" } else { "s" }),
+    lengths_unavailable: "longueurs des segments indisponibles, comptés à égalité",
+
+    no_speakers: "Aucune étiquette d'intervenant disponible",
+    speaker_name: |n| format!("Intervenant {}", n),
+    speaker_summary: |speakers, duration, interruptions| {
+        format!(
+            "{} intervenant{} sur {} de parole, {} interruption{}.",
+            speakers,
+            if speakers == 1 { "" } else { "s" },
+            duration,
+            interruptions,
+            if interruptions <= 1 { "" } else { "s" }
+        )
+    },
+    speaker: "Intervenant",
+    talk_time: "Temps de parole",
+    share: "Part",
+    turns: "Tours",
+    average_turn: "Tour moyen",
+    longest_monologue: "Plus long monologue",
+    interruptions: "Interruptions",
+    rate: "Débit",
+    words_per_minute: "mots/min",
+};
+
+static DE: Locale = Locale {
+    language: "de",
+
+    title: "Analyseergebnisse für",
+    generated_on: "Erstellt am",
+    overview: "Überblick",
+    main_topics: "Hauptthemen",
+    topic_map: "Themenkarte",
+    other_topics: "Sonstiges",
+    action_items: "Aufgaben",
+    decisions: "Entscheidungen",
+    open_questions: "Offene Fragen",
+    key_phrases: "Schlüsselbegriffe",
+    sentiment_analysis: "Stimmungsanalyse",
+    speaker_analytics: "Sprecheranalyse",
+    named_entities: "Benannte Entitäten",
+    chunk_summaries: "Ausführliche Abschnittszusammenfassungen",
+    chunk_summary: |n| format!("Zusammenfassung Abschnitt {}", n),
+    topics: "Themen",
+    appendix_note: |target| {
+        format!(
+            "Die Zusammenfassung jedes Abschnitts steht im [Anhang](<{}>).",
+            target
+        )
+    },
+    back_to_report: "Zurück zum Bericht",
+    timeline: "Zeitverlauf",
+    comprehend_fallback: |language| {
+        format!(
+            "Comprehend unterstützt {} nicht; Entitäten, Schlüsselbegriffe und Stimmung wurden als Englisch analysiert.",
+            language
+        )
+    },
+    unknown_citations: |sections| {
+        format!(
+            "Der Überblick verweist auf Abschnitte, die es nicht gibt: {}.",
+            sections
+        )
+    },
+
+    quality_warnings: "Qualitätswarnungen",
+    empty_transcript: "Es wurde keine Sprache transkribiert.",
+    empty_chunk: |chunk| format!("Der Text von {} ist leer.", chunk),
+    truncated: |output| format!("Am Token-Limit des Modells abgeschnitten: {}.", output),
+    unreadable_outcomes: |chunk| {
+        format!(
+            "Aufgaben, Entscheidungen und offene Fragen von {} konnten nicht gelesen werden.",
+            chunk
+        )
+    },
+    missing_analysis: |analysis, missing, total| {
+        format!(
+            "{}: keine Ergebnisse für {} von {} Abschnitten.",
+            analysis, missing, total
+        )
+    },
+    low_confidence: |chunk, confidence| {
+        format!(
+            "Die Transkriptionskonfidenz in {} beträgt nur {}.",
+            chunk, confidence
+        )
+    },
+
+    chunk_label: |n| format!("Abschnitt {}", n),
+    transcript_offset: "Position im Transkript",
+    at_time: |time| format!("bei {}", time),
+    in_chunk: |link| format!("in {}", link),
+
+    no_action_items: "Keine Aufgaben gefunden",
+    no_decisions: "Keine Entscheidungen gefunden",
+    no_open_questions: "Keine offenen Fragen gefunden",
+    owner: "Verantwortlich",
+    task: "Aufgabe",
+    due: "Fällig",
+    source: "Quelle",
+
+    no_key_phrases: "Keine Schlüsselbegriffe erkannt",
+    phrase: "Begriff",
+    mentions: "Nennungen",
+    location: "Wo",
+    score: "Bewertung",
+
+    no_entities: "Keine Entitäten erkannt",
+    entity: "Entität",
+    first: "Erste",
+    last: "Letzte",
+    variants: "Varianten",
+    confidence: "Konfidenz",
+    context: "Kontext",
+    and_more: |n| format!("…und {} weitere", n),
+
+    sentiment_labels: ["Positiv", "Negativ", "Neutral", "Gemischt"],
+    overall_sentiment: "Gesamtstimmung",
+    aggregation: "Aggregation",
+    confidence_scores: "Konfidenzwerte",
+    arc: "Verlauf",
+    arc_legend: "netto positiv − negativ je Abschnitt",
+    no_sentiment: "Keine Stimmungsdaten verfügbar",
+    chunk: "Abschnitt",
+    time: "Zeit",
+    sentiment: "Stimmung",
+    transitions: "Wechsel",
+    turned: |label, position| format!("Wechsel zu {} {}", label, position),
+    stayed: |label| format!("Die Stimmung blieb durchgehend {}.", label),
+    majority: "Mehrheitsentscheid, alle Abschnitte gleich gewichtet",
+    length_weighted: "gewichtet nach Abschnittslänge",
+    confidence_weighted:
+        "gewichtet nach Abschnittslänge und Konfidenz der vorherrschenden Stimmung",
+    across_chunks: |n| format!("über {} Abschnitt{}", n, if n == 1 { "" } else { "e" }),
+    lengths_unavailable: "Abschnittslängen nicht verfügbar, gleich gewichtet",
+
+    no_speakers: "Keine Sprecherkennzeichnungen verfügbar",
+    speaker_name: |n| format!("Sprecher {}", n),
+    speaker_summary: |speakers, duration, interruptions| {
+        format!(
+            "{} Sprecher in {} Sprechzeit, {} Unterbrechung{}.",
+            speakers,
+            duration,
+            interruptions,
+            if interruptions == 1 { "" } else { "en" }
+        )
+    },
+    speaker: "Sprecher",
+    talk_time: "Sprechzeit",
+    share: "Anteil",
+    turns: "Beiträge",
+    average_turn: "Ø Beitrag",
+    longest_monologue: "Längster Monolog",
+    interruptions: "Unterbrechungen",
+    rate: "Tempo",
+    words_per_minute: "Wörter/min",
+};
+
+static PT: Locale = Locale {
+    language: "pt",
+
+    title: "Resultados da análise de",
+    generated_on: "Gerado em",
+    overview: "Visão geral",
+    main_topics: "Tópicos principais",
+    topic_map: "Mapa de tópicos",
+    other_topics: "Outros",
+    action_items: "Itens de ação",
+    decisions: "Decisões",
+    open_questions: "Perguntas em aberto",
+    key_phrases: "Frases-chave",
+    sentiment_analysis: "Análise de sentimento",
+    speaker_analytics: "Análise de falantes",
+    named_entities: "Entidades nomeadas",
+    chunk_summaries: "Resumos detalhados por seção",
+    chunk_summary: |n| format!("Resumo do trecho {}", n),
+    topics: "Tópicos",
+    appendix_note: |target| format!("O resumo de cada trecho está no [apêndice](<{}>).", target),
+    back_to_report: "Voltar ao relatório",
+    timeline: "Linha do tempo",
+    comprehend_fallback: |language| {
+        format!(
+            "O Comprehend não oferece suporte a {}; entidades, frases-chave e sentimento foram analisados como inglês.",
+            language
+        )
+    },
+    unknown_citations: |sections| {
+        format!("A visão geral cita seções que não existem: {}.", sections)
+    },
+
+    quality_warnings: "Avisos de qualidade",
+    empty_transcript: "Nenhuma fala foi transcrita.",
+    empty_chunk: |chunk| format!("O texto do {} está vazio.", chunk),
+    truncated: |output| format!("Cortado no limite de tokens do modelo: {}.", output),
+    unreadable_outcomes: |chunk| {
+        format!(
+            "Não foi possível ler os itens de ação, decisões e perguntas em aberto do {}.",
+            chunk
+        )
+    },
+    missing_analysis: |analysis, missing, total| {
+        format!(
+            "{}: sem resultados para {} de {} trechos.",
+            analysis, missing, total
+        )
+    },
+    low_confidence: |chunk, confidence| {
+        format!(
+            "A confiança da transcrição no {} é de apenas {}.",
+            chunk, confidence
+        )
+    },
+
+    chunk_label: |n| format!("trecho {}", n),
+    transcript_offset: "posição na transcrição",
+    at_time: |time| format!("em {}", time),
+    in_chunk: |link| format!("no {}", link),
+
+    no_action_items: "Nenhum item de ação identificado",
+    no_decisions: "Nenhuma decisão identificada",
+    no_open_questions: "Nenhuma pergunta em aberto identificada",
+    owner: "Responsável",
+    task: "Tarefa",
+    due: "Prazo",
+    source: "Origem",
+
+    no_key_phrases: "Nenhuma frase-chave detectada",
+    phrase: "Frase",
+    mentions: "Menções",
+    location: "Onde",
+    score: "Pontuação",
+
+    no_entities: "Nenhuma entidade detectada",
+    entity: "Entidade",
+    first: "Primeira",
+    last: "Última",
+    variants: "Variantes",
+    confidence: "Confiança",
+    context: "Contexto",
+    and_more: |n| format!("…e mais {}", n),
+
+    sentiment_labels: ["Positivo", "Negativo", "Neutro", "Misto"],
+    overall_sentiment: "Sentimento geral",
+    aggregation: "Agregação",
+    confidence_scores: "Pontuações de confiança",
+    arc: "Arco",
+    arc_legend: "positivo líquido − negativo por trecho",
+    no_sentiment: "Nenhum dado de sentimento disponível",
+    chunk: "Trecho",
+    time: "Tempo",
+    sentiment: "Sentimento",
+    transitions: "Transições",
+    turned: |label, position| format!("Passou a {} {}", label, position),
+    stayed: |label| format!("O sentimento permaneceu {} do início ao fim.", label),
+    majority: "voto da maioria, trechos com o mesmo peso",
+    length_weighted: "ponderado pelo tamanho do trecho",
+    confidence_weighted: "ponderado pelo tamanho do trecho e pela confiança do rótulo vencedor",
+    across_chunks: |n| format!("em {} trecho{}", n, if n == 1 { "" } else { "s" }),
+    lengths_unavailable: "tamanhos dos trechos indisponíveis, contados igualmente",
+
+    no_speakers: "Nenhum rótulo de falante disponível",
+    speaker_name: |n| format!("Falante {}", n),
+    speaker_summary: |speakers, duration, interruptions| {
+        format!(
+            "{} falante{} em {} de fala, {} interrup{}.",
+            speakers,
+            if speakers == 1 { "" } else { "s" },
+            duration,
+            interruptions,
+            if interruptions == 1 {
+                "ção"
+            } else {
+                "ções"
+            }
+        )
+    },
+    speaker: "Falante",
+    talk_time: "Tempo de fala",
+    share: "Participação",
+    turns: "Turnos",
+    average_turn: "Turno médio",
+    longest_monologue: "Monólogo mais longo",
+    interruptions: "Interrupções",
+    rate: "Ritmo",
+    words_per_minute: "ppm",
+};
+
+static IT: Locale = Locale {
+    language: "it",
+
+    title: "Risultati dell'analisi di",
+    generated_on: "Generato il",
+    overview: "Panoramica",
+    main_topics: "Temi principali",
+    topic_map: "Mappa dei temi",
+    other_topics: "Altro",
+    action_items: "Azioni da svolgere",
+    decisions: "Decisioni",
+    open_questions: "Domande aperte",
+    key_phrases: "Frasi chiave",
+    sentiment_analysis: "Analisi del sentiment",
+    speaker_analytics: "Analisi dei parlanti",
+    named_entities: "Entità denominate",
+    chunk_summaries: "Riepiloghi dettagliati per sezione",
+    chunk_summary: |n| format!("Riepilogo del segmento {}", n),
+    topics: "Temi",
+    appendix_note: |target| {
+        format!(
+            "Il riepilogo di ogni segmento si trova nell'[appendice](<{}>).",
+            target
+        )
+    },
+    back_to_report: "Torna al report",
+    timeline: "Cronologia",
+    comprehend_fallback: |language| {
+        format!(
+            "Comprehend non supporta {}; entità, frasi chiave e sentiment sono stati analizzati come inglese.",
+            language
+        )
+    },
+    unknown_citations: |sections| format!("La panoramica cita sezioni inesistenti: {}.", sections),
+
+    quality_warnings: "Avvisi di qualità",
+    empty_transcript: "Non è stato trascritto alcun parlato.",
+    empty_chunk: |chunk| format!("Il testo del {} è vuoto.", chunk),
+    truncated: |output| format!("Troncato al limite di token del modello: {}.", output),
+    unreadable_outcomes: |chunk| {
+        format!(
+            "Non è stato possibile leggere azioni, decisioni e domande aperte del {}.",
+            chunk
+        )
+    },
+    missing_analysis: |analysis, missing, total| {
+        format!(
+            "{}: nessun risultato per {} segmenti su {}.",
+            analysis, missing, total
+        )
+    },
+    low_confidence: |chunk, confidence| {
+        format!(
+            "L'affidabilità della trascrizione nel {} è solo del {}.",
+            chunk, confidence
+        )
+    },
+
+    chunk_label: |n| format!("segmento {}", n),
+    transcript_offset: "posizione nella trascrizione",
+    at_time: |time| format!("a {}", time),
+    in_chunk: |link| format!("nel {}", link),
+
+    no_action_items: "Nessuna azione individuata",
+    no_decisions: "Nessuna decisione individuata",
+    no_open_questions: "Nessuna domanda aperta individuata",
+    owner: "Responsabile",
+    task: "Attività",
+    due: "Scadenza",
+    source: "Fonte",
+
+    no_key_phrases: "Nessuna frase chiave rilevata",
+    phrase: "Frase",
+    mentions: "Menzioni",
+    location: "Dove",
+    score: "Punteggio",
+
+    no_entities: "Nessuna entità rilevata",
+    entity: "Entità",
+    first: "Prima",
+    last: "Ultima",
+    variants: "Varianti",
+    confidence: "Affidabilità",
+    context: "Contesto",
+    and_more: |n| format!("…e altri {}", n),
+
+    sentiment_labels: ["Positivo", "Negativo", "Neutro", "Misto"],
+    overall_sentiment: "Sentiment complessivo",
+    aggregation: "Aggregazione",
+    confidence_scores: "Punteggi di affidabilità",
+    arc: "Andamento",
+    arc_legend: "positivo netto − negativo per segmento",
+    no_sentiment: "Nessun dato di sentiment disponibile",
+    chunk: "Segmento",
+    time: "Tempo",
+    sentiment: "Sentiment",
+    transitions: "Transizioni",
+    turned: |label, position| format!("Diventato {} {}", label, position),
+    stayed: |label| format!("Il sentiment è rimasto {} per tutto il tempo.", label),
+    majority: "voto di maggioranza, segmenti con lo stesso peso",
+    length_weighted: "ponderato per la lunghezza del segmento",
+    confidence_weighted:
+        "ponderato per la lunghezza del segmento e l'affidabilità dell'etichetta prevalente",
+    across_chunks: |n| format!("su {} segment{}", n, if n == 1 { "o" } else { "i" }),
+    lengths_unavailable: "lunghezze dei segmenti non disponibili, contati allo stesso modo",
+
+    no_speakers: "Nessuna etichetta dei parlanti disponibile",
+    speaker_name: |n| format!("Parlante {}", n),
+    speaker_summary: |speakers, duration, interruptions| {
+        format!(
+            "{} parlant{} in {} di parlato, {} interruzion{}.",
+            speakers,
+            if speakers == 1 { "e" } else { "i" },
+            duration,
+            interruptions,
+            if interruptions == 1 { "e" } else { "i" }
+        )
+    },
+    speaker: "Parlante",
+    talk_time: "Tempo di parola",
+    share: "Quota",
+    turns: "Turni",
+    average_turn: "Turno medio",
+    longest_monologue: "Monologo più lungo",
+    interruptions: "Interruzioni",
+    rate: "Velocità",
+    words_per_minute: "parole/min",
+};
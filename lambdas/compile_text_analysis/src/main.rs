@@ -1,9 +1,41 @@
 use anyhow::Result;
-use chrono::Utc;
+use chrono::{DateTime, SecondsFormat, Utc};
 use lambda_runtime::{run, service_fn, Error, LambdaEvent};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::BTreeMap;
+
+mod citations;
+mod config;
+mod diagnostics;
+mod entities;
+#[cfg(test)]
+mod golden_tests;
+mod key_phrases;
+mod locale;
+mod mind_map;
+mod outcomes;
+mod provenance;
+mod sentiment;
+mod speakers;
+mod summaries;
+mod transcript;
+
+use citations::link_citations;
+use config::{ReportConfig, ReportSection};
+use diagnostics::{collect_warnings, format_quality_warnings, QualityWarning};
+use entities::{format_entities, EntityAliases, EntityData};
+use key_phrases::{format_key_phrases, KeyPhraseData};
+use locale::Locale;
+use mind_map::{build_topic_tree, parse_topics};
+use outcomes::{
+    format_action_items, format_decisions, format_open_questions, parse_outcomes, ChunkOutcomes,
+};
+use provenance::{PipelineDetails, Provenance};
+use sentiment::{format_sentiment_section, SentimentData};
+use speakers::{format_speaker_analytics, SpeakerTurn};
+use summaries::{format_appendix, format_appendix_contents, format_chunk_summaries};
+use transcript::ChunkMetadata;
 
 #[derive(Debug, Deserialize)]
 struct AnalysisResult {
@@ -12,38 +44,66 @@ struct AnalysisResult {
     chunk_summaries: Vec<ChunkAnalysis>,
     sentiment: Vec<Vec<SentimentData>>,
     entities: Vec<Vec<EntityData>>,
+    /// Absent when the pipeline didn't run key phrase detection.
+    #[serde(default)]
+    key_phrases: Option<Vec<Vec<KeyPhraseData>>>,
+    #[serde(default)]
+    chunk_metadata: Vec<ChunkMetadata>,
+    #[serde(default)]
+    speaker_turns: Vec<SpeakerTurn>,
+    #[serde(default)]
+    entity_aliases: EntityAliases,
+    /// The report config document loaded from S3, if one was given.
+    #[serde(default)]
+    report_config: Value,
+    #[serde(default)]
+    execution_input: ExecutionInput,
+    #[serde(default)]
+    provenance: PipelineDetails,
+    #[serde(default)]
+    language: Option<AnalysisLanguage>,
     key: String,
 }
 
+/// The fields of the execution input that shape the report.
+#[derive(Debug, Deserialize, Default)]
+struct ExecutionInput {
+    #[serde(rename = "languageCode")]
+    language_code: Option<String>,
+    #[serde(default)]
+    options: Value,
+}
+
+/// The language Comprehend analyzed the chunks in, as chosen by the extract-transcript lambda.
+#[derive(Debug, Deserialize)]
+struct AnalysisLanguage {
+    transcribe: Option<String>,
+    /// Set when Comprehend doesn't support the transcript's language and English was used.
+    fallback: bool,
+}
+
 #[derive(Debug, Deserialize)]
 struct ChunkAnalysis {
-    chunkAnalysis: Vec<BedrockWrapper>,
+    #[serde(default)]
+    chunk: String,
+    #[serde(rename = "chunkAnalysis")]
+    chunk_analysis: Vec<BedrockWrapper>,
 }
 
 #[derive(Debug, Deserialize)]
 struct BedrockWrapper {
-    Body: BedrockMessage,
-    ContentType: String,
+    #[serde(rename = "Body")]
+    body: BedrockMessage,
 }
 
 #[derive(Debug, Deserialize)]
 struct BedrockMessage {
-    id: String,
-    #[serde(rename = "type")]
-    message_type: String,
-    role: String,
-    model: String,
+    #[serde(default)]
+    model: Option<String>,
+    /// `max_tokens` when the model ran out of room before finishing.
+    #[serde(default)]
+    stop_reason: Option<String>,
     content: Vec<ContentBlock>,
-    stop_reason: String,
-    #[serde(rename = "stop_sequence")]
-    stop_sequence: Option<String>,
-    usage: Usage,
-}
-
-#[derive(Debug, Deserialize)]
-struct Usage {
-    input_tokens: i32,
-    output_tokens: i32,
 }
 
 #[derive(Debug, Deserialize)]
@@ -53,156 +113,23 @@ struct ContentBlock {
     text: String,
 }
 
-#[derive(Debug, Deserialize, Clone)]
-struct SentimentData {
-    Sentiment: String,
-    SentimentScore: HashMap<String, f64>,
-}
-
-#[derive(Debug, Deserialize)]
-struct Entity {
-    Text: String,
-    Type: String,
-    Score: f64,
-    BeginOffset: i32,
-    EndOffset: i32,
-}
-
-#[derive(Debug, Deserialize)]
-struct EntityData {
-    Entities: Vec<Entity>,
-}
-
 #[derive(Serialize)]
 struct Response {
-    statusCode: i32,
+    #[serde(rename = "statusCode")]
+    status_code: i32,
     body: String,
-    headers: HashMap<String, String>,
-}
-
-fn format_sentiment_score(score: f64) -> String {
-    format!("{:.1}%", score * 100.0)
-}
-
-fn get_sentiment_emoji(sentiment: &str) -> &'static str {
-    match sentiment {
-        "POSITIVE" => "😊",
-        "NEGATIVE" => "😔",
-        "NEUTRAL" => "😐",
-        "MIXED" => "🤔",
-        _ => "",
-    }
-}
-
-fn combine_sentiment_data(sentiments: &[Vec<SentimentData>]) -> SentimentData {
-    // Flatten the nested arrays
-    let flattened: Vec<&SentimentData> = sentiments.iter().flat_map(|inner| inner.iter()).collect();
-
-    if flattened.is_empty() {
-        return SentimentData {
-            Sentiment: "NEUTRAL".to_string(),
-            SentimentScore: HashMap::new(),
-        };
-    }
-
-    // Count occurrences of each sentiment
-    let mut sentiment_counts: HashMap<String, usize> = HashMap::new();
-    let mut total_scores: HashMap<String, f64> = HashMap::new();
-    let sentiment_count = flattened.len() as f64;
-
-    for sentiment in flattened {
-        *sentiment_counts
-            .entry(sentiment.Sentiment.clone())
-            .or_default() += 1;
-
-        for (key, &score) in &sentiment.SentimentScore {
-            *total_scores.entry(key.clone()).or_default() += score;
-        }
-    }
-
-    // Find the most common sentiment
-    let overall_sentiment = sentiment_counts
-        .iter()
-        .max_by_key(|&(_, count)| *count)
-        .map(|(sentiment, _)| sentiment.clone())
-        .unwrap_or_else(|| "NEUTRAL".to_string());
-
-    // Average the scores
-    let mut average_scores = HashMap::new();
-    for (key, total) in total_scores {
-        average_scores.insert(key, total / sentiment_count);
-    }
-
-    SentimentData {
-        Sentiment: overall_sentiment,
-        SentimentScore: average_scores,
-    }
-}
-
-fn format_entities(entities_chunks: &[Vec<EntityData>]) -> String {
-    // Flatten and collect all entities
-    let all_entities: Vec<&Entity> = entities_chunks
-        .iter()
-        .flat_map(|chunk| chunk.iter())
-        .flat_map(|data| data.Entities.iter())
-        .collect();
-
-    if all_entities.is_empty() {
-        return String::from("No entities detected");
-    }
-
-    // Deduplicate entities by text and type, keeping the highest confidence score
-    let mut unique_entities: HashMap<(String, String), &Entity> = HashMap::new();
-    for entity in all_entities.iter() {
-        unique_entities
-            .entry((entity.Text.clone(), entity.Type.clone()))
-            .and_modify(|e| {
-                if entity.Score > e.Score {
-                    *e = entity;
-                }
-            })
-            .or_insert(entity);
-    }
-
-    // Group by entity type
-    let mut entity_groups: HashMap<String, Vec<&Entity>> = HashMap::new();
-    for entity in unique_entities.values() {
-        entity_groups
-            .entry(entity.Type.clone())
-            .or_default()
-            .push(entity);
-    }
-
-    let mut sections = Vec::new();
-    for (entity_type, entities) in entity_groups {
-        // Sort entities by confidence score
-        let mut entities = entities.to_vec();
-        entities.sort_by(|a, b| {
-            b.Score
-                .partial_cmp(&a.Score)
-                .unwrap_or(std::cmp::Ordering::Equal)
-        });
-
-        let entity_texts: Vec<String> = entities
-            .iter()
-            .map(|entity| {
-                format!(
-                    "- {} (confidence: {:.1}%)",
-                    entity.Text,
-                    entity.Score * 100.0
-                )
-            })
-            .collect();
-
-        sections.push(format!("### {}\n{}", entity_type, entity_texts.join("\n")));
-    }
-
-    sections.join("\n\n")
+    headers: BTreeMap<String, String>,
+    provenance: Provenance,
+    /// The topic map as a standalone SVG, for renderers without Mermaid.
+    mind_map_svg: Option<String>,
+    /// The chunk summaries, when the config moves them out of the report.
+    appendix: Option<String>,
+    warnings: Vec<QualityWarning>,
 }
 
 fn extract_text(bedrock_response: &BedrockWrapper) -> String {
     bedrock_response
-        .Body
+        .body
         .content
         .iter()
         .filter(|block| block.content_type == "text")
@@ -211,101 +138,226 @@ fn extract_text(bedrock_response: &BedrockWrapper) -> String {
         .join("\n")
 }
 
-fn format_chunk_summaries(chunk_summaries: &[ChunkAnalysis]) -> String {
-    chunk_summaries
-        .iter()
-        .enumerate()
-        .map(|(i, chunk)| {
-            let summary = extract_text(&chunk.chunkAnalysis[0]);
-            let topics = extract_text(&chunk.chunkAnalysis[1]);
-            format!(
-                "### Chunk {} Summary\n{}\n\n#### Topics\n{}",
-                i + 1,
-                summary.trim(),
-                topics.trim()
-            )
-        })
-        .collect::<Vec<String>>()
-        .join("\n\n")
-}
-
-async fn function_handler(event: LambdaEvent<Value>) -> Result<Response, Error> {
-    let analysis: AnalysisResult = serde_json::from_value(event.payload)?;
+/// Renders the report for the pipeline's output. The same payload and `generated_at` always
+/// give the same response.
+fn compile_report(payload: Value, generated_at: DateTime<Utc>) -> Result<Response, Error> {
+    let analysis: AnalysisResult = serde_json::from_value(payload)?;
 
-    let overview = extract_text(&analysis.overview);
-    let main_topics = extract_text(&analysis.main_topics);
-    let combined_sentiment = combine_sentiment_data(&analysis.sentiment);
-    let sentiment_emoji = get_sentiment_emoji(&combined_sentiment.Sentiment);
+    let config = ReportConfig::resolve(&analysis.report_config, &analysis.execution_input.options)
+        .map_err(|e| Error::from(format!("Invalid report config: {}", e)))?;
+    let locale = Locale::for_language(analysis.execution_input.language_code.as_deref());
 
-    let markdown = format!(
-        r#"# Analysis Results for {}
-Generated on {} UTC
+    let chunk_texts: Vec<&str> = analysis
+        .chunk_summaries
+        .iter()
+        .map(|chunk| chunk.chunk.as_str())
+        .collect();
 
-## Overview
-{}
+    // Chunks whose meeting outcomes couldn't be parsed are left out of those sections
+    let outcomes: Vec<(usize, ChunkOutcomes)> = analysis
+        .chunk_summaries
+        .iter()
+        .enumerate()
+        .filter_map(|(i, chunk)| {
+            let text = extract_text(chunk.chunk_analysis.get(2)?);
+            Some((i, parse_outcomes(&text)?))
+        })
+        .collect();
 
-## Main Topics
-{}
+    let warnings = collect_warnings(&analysis, &config);
+
+    let topic_tree = config
+        .sections
+        .contains(&ReportSection::MindMap)
+        .then(|| {
+            let chunk_topics: Vec<String> = analysis
+                .chunk_summaries
+                .iter()
+                .filter_map(|chunk| chunk.chunk_analysis.get(1))
+                .flat_map(|topics| parse_topics(&extract_text(topics)))
+                .collect();
+            build_topic_tree(
+                &analysis.key,
+                &parse_topics(&extract_text(&analysis.main_topics)),
+                &chunk_topics,
+                locale.other_topics,
+            )
+        })
+        .filter(|tree| !tree.branches.is_empty());
 
-## Sentiment Analysis {}
-Overall sentiment: **{}**
+    let sections: Vec<String> = config
+        .sections
+        .iter()
+        .map(|section| match section {
+            ReportSection::Overview => format!(
+                "## {}\n{}",
+                locale.overview,
+                link_citations(
+                    &extract_text(&analysis.overview),
+                    &analysis.chunk_metadata,
+                    analysis.chunk_summaries.len(),
+                )
+                .0
+            ),
+            ReportSection::MainTopics => {
+                format!(
+                    "## {}\n{}",
+                    locale.main_topics,
+                    extract_text(&analysis.main_topics)
+                )
+            }
+            ReportSection::MindMap => format!(
+                "## {}\n{}",
+                locale.topic_map,
+                topic_tree
+                    .as_ref()
+                    .map(|tree| tree.to_mermaid())
+                    .unwrap_or_else(|| extract_text(&analysis.main_topics))
+            ),
+            ReportSection::ActionItems => format!(
+                "## {}\n{}",
+                locale.action_items,
+                format_action_items(&outcomes, &chunk_texts, &analysis.chunk_metadata, locale)
+            ),
+            ReportSection::Decisions => format!(
+                "## {}\n{}",
+                locale.decisions,
+                format_decisions(&outcomes, &chunk_texts, &analysis.chunk_metadata, locale)
+            ),
+            ReportSection::OpenQuestions => format!(
+                "## {}\n{}",
+                locale.open_questions,
+                format_open_questions(&outcomes, &chunk_texts, &analysis.chunk_metadata, locale)
+            ),
+            ReportSection::KeyPhrases => format!(
+                "## {}\n{}",
+                locale.key_phrases,
+                format_key_phrases(
+                    analysis.key_phrases.as_deref().unwrap_or_default(),
+                    &analysis.chunk_metadata,
+                    &config,
+                    locale
+                )
+            ),
+            ReportSection::Sentiment => format_sentiment_section(
+                &analysis.sentiment,
+                &analysis.chunk_metadata,
+                config.sentiment_aggregation,
+                config.sentiment_style,
+                locale,
+            ),
+            ReportSection::Speakers => format!(
+                "## {}\n{}",
+                locale.speaker_analytics,
+                format_speaker_analytics(&analysis.speaker_turns, &analysis.chunk_metadata, locale)
+            ),
+            ReportSection::Entities => format!(
+                "## {}\n{}",
+                locale.named_entities,
+                format_entities(
+                    &analysis.entities,
+                    &analysis.chunk_metadata,
+                    &chunk_texts,
+                    &analysis.entity_aliases,
+                    &config,
+                    locale,
+                )
+            ),
+            ReportSection::ChunkSummaries if config.chunk_summary_appendix => format!(
+                "## {}\n{}",
+                locale.chunk_summaries,
+                format_appendix_contents(
+                    analysis.chunk_summaries.len(),
+                    &analysis.chunk_metadata,
+                    &analysis.key,
+                    locale
+                )
+            ),
+            ReportSection::ChunkSummaries => format!(
+                "## {}\n{}",
+                locale.chunk_summaries,
+                format_chunk_summaries(
+                    &analysis.chunk_summaries,
+                    &analysis.chunk_metadata,
+                    config.collapse_chunk_summaries,
+                    locale
+                )
+            ),
+        })
+        .collect();
 
-Confidence Scores:
-- Positive: {}
-- Negative: {}
-- Neutral: {}
-- Mixed: {}
+    let appendix = (config.chunk_summary_appendix
+        && config.sections.contains(&ReportSection::ChunkSummaries))
+    .then(|| {
+        format_appendix(
+            &analysis.key,
+            &format_chunk_summaries(
+                &analysis.chunk_summaries,
+                &analysis.chunk_metadata,
+                config.collapse_chunk_summaries,
+                locale,
+            ),
+            locale,
+        )
+    });
+
+    let models: Vec<String> = [&analysis.overview, &analysis.main_topics]
+        .into_iter()
+        .chain(
+            analysis
+                .chunk_summaries
+                .iter()
+                .flat_map(|chunk| &chunk.chunk_analysis),
+        )
+        .filter_map(|response| response.body.model.clone())
+        .collect();
+    let provenance = Provenance::new(
+        analysis.provenance,
+        &analysis.key,
+        models,
+        analysis.chunk_summaries.len(),
+        generated_at.to_rfc3339_opts(SecondsFormat::Secs, true),
+    );
 
-## Named Entities
-{}
+    // Say so up front when the NLP sections weren't analyzed in the transcript's language
+    let language_note = match &analysis.language {
+        Some(AnalysisLanguage {
+            transcribe: Some(language),
+            fallback: true,
+        }) => format!("> {}\n\n", (locale.comprehend_fallback)(language)),
+        _ => String::new(),
+    };
 
-## Detailed Section Summaries
-{}
-"#,
+    let markdown = format!(
+        "{}\n\n# {} {}\n{} {} UTC\n\n{}{}{}\n",
+        provenance.front_matter(),
+        locale.title,
         analysis.key,
-        Utc::now().format("%Y-%m-%d %H:%M:%S"),
-        overview,
-        main_topics,
-        sentiment_emoji,
-        combined_sentiment.Sentiment.to_lowercase(),
-        format_sentiment_score(
-            *combined_sentiment
-                .SentimentScore
-                .get("Positive")
-                .unwrap_or(&0.0)
-        ),
-        format_sentiment_score(
-            *combined_sentiment
-                .SentimentScore
-                .get("Negative")
-                .unwrap_or(&0.0)
-        ),
-        format_sentiment_score(
-            *combined_sentiment
-                .SentimentScore
-                .get("Neutral")
-                .unwrap_or(&0.0)
-        ),
-        format_sentiment_score(
-            *combined_sentiment
-                .SentimentScore
-                .get("Mixed")
-                .unwrap_or(&0.0)
-        ),
-        format_entities(&analysis.entities),
-        format_chunk_summaries(&analysis.chunk_summaries)
+        locale.generated_on,
+        generated_at.format("%Y-%m-%d %H:%M:%S"),
+        language_note,
+        format_quality_warnings(&warnings, locale),
+        sections.join("\n\n")
     );
 
-    let mut headers = HashMap::new();
+    let mut headers = BTreeMap::new();
     headers.insert(String::from("Content-Type"), String::from("text/markdown"));
 
     Ok(Response {
-        statusCode: 200,
+        status_code: 200,
         body: markdown,
         headers,
+        provenance,
+        mind_map_svg: topic_tree.map(|tree| tree.to_svg()),
+        appendix,
+        warnings,
     })
 }
 
+async fn function_handler(event: LambdaEvent<Value>) -> Result<Response, Error> {
+    compile_report(event.payload, Utc::now())
+}
+
 #[tokio::main]
 async fn main() -> Result<(), Error> {
     tracing_subscriber::fmt()
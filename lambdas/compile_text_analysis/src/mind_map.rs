@@ -0,0 +1,285 @@
+use crate::entities::tokenize;
+use std::collections::BTreeSet;
+
+/// Words that say nothing about which topic a chunk topic belongs to.
+const STOP_WORDS: [&str; 14] = [
+    "a", "an", "and", "as", "at", "by", "for", "in", "of", "on", "or", "the", "to", "with",
+];
+
+/// Longer topics are cut so the diagram stays readable.
+const MAX_TOPIC_CHARS: usize = 60;
+
+// SVG layout, in pixels
+const ROW_HEIGHT: usize = 28;
+const CHAR_WIDTH: usize = 7;
+const COLUMN_GAP: usize = 60;
+const MARGIN: usize = 20;
+
+/// Consolidated topics with the chunk topics that best match each of them.
+pub(crate) struct TopicTree {
+    pub(crate) root: String,
+    pub(crate) branches: Vec<(String, Vec<String>)>,
+}
+
+/// Pulls topic names out of a markdown bullet list such as `- **Budget**: the Q3 numbers`.
+pub(crate) fn parse_topics(text: &str) -> Vec<String> {
+    text.lines()
+        .map(str::trim)
+        .filter_map(|line| {
+            let item = line
+                .strip_prefix(['-', '*', '•'])
+                .or_else(|| {
+                    let digits = line.len() - line.trim_start_matches(char::is_numeric).len();
+                    (digits > 0)
+                        .then(|| line[digits..].strip_prefix(['.', ')']))
+                        .flatten()
+                })
+                // A marker needs a space after it, which rules out rules (`---`) and bold
+                // headings (`**Topics:**`)
+                .filter(|rest| rest.starts_with(char::is_whitespace))?
+                .trim();
+
+            // Prefer the bold label, then whatever comes before a description
+            let topic = match item
+                .strip_prefix("**")
+                .and_then(|rest| rest.split_once("**"))
+            {
+                Some((bold, _)) => bold,
+                None => item.split_once(": ").map_or(item, |(label, _)| label),
+            };
+            let topic = topic.trim().trim_end_matches([':', '.', ',']).trim();
+
+            topic
+                .contains(char::is_alphanumeric)
+                .then(|| truncate(topic))
+        })
+        .collect()
+}
+
+fn truncate(text: &str) -> String {
+    if text.chars().count() <= MAX_TOPIC_CHARS {
+        text.to_string()
+    } else {
+        let cut: String = text.chars().take(MAX_TOPIC_CHARS - 1).collect();
+        format!("{}…", cut.trim_end())
+    }
+}
+
+fn keywords(text: &str) -> BTreeSet<String> {
+    tokenize(text, true)
+        .into_iter()
+        .filter(|token| !STOP_WORDS.contains(&token.as_str()))
+        .collect()
+}
+
+/// Groups every chunk topic under the consolidated topic sharing the most keywords with it, or
+/// under `other` when none share any.
+pub(crate) fn build_topic_tree(
+    root: &str,
+    main_topics: &[String],
+    chunk_topics: &[String],
+    other: &str,
+) -> TopicTree {
+    let main_keywords: Vec<BTreeSet<String>> =
+        main_topics.iter().map(|topic| keywords(topic)).collect();
+
+    let mut branches: Vec<(String, Vec<String>)> = main_topics
+        .iter()
+        .map(|topic| (topic.clone(), Vec::new()))
+        .collect();
+    let mut unmatched = Vec::new();
+    let mut seen = BTreeSet::new();
+
+    for topic in chunk_topics {
+        let words = keywords(topic);
+        if !seen.insert(words.iter().cloned().collect::<Vec<String>>().join(" ")) {
+            continue;
+        }
+
+        // The earliest consolidated topic wins ties
+        let best = main_keywords
+            .iter()
+            .enumerate()
+            .map(|(i, main)| (i, main.intersection(&words).count()))
+            .filter(|&(_, overlap)| overlap > 0)
+            .max_by(|(a, a_overlap), (b, b_overlap)| a_overlap.cmp(b_overlap).then(b.cmp(a)));
+
+        match best {
+            Some((i, _)) => branches[i].1.push(topic.clone()),
+            None => unmatched.push(topic.clone()),
+        }
+    }
+
+    if !unmatched.is_empty() {
+        branches.push((other.to_string(), unmatched));
+    }
+
+    TopicTree {
+        root: root.to_string(),
+        branches,
+    }
+}
+
+/// Mermaid gives brackets and parentheses a meaning in node text, so they are dropped.
+fn mermaid_text(text: &str) -> String {
+    text.chars()
+        .filter(|c| !"()[]{}\"`".contains(*c))
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<&str>>()
+        .join(" ")
+}
+
+fn text_width(text: &str) -> usize {
+    text.chars().count() * CHAR_WIDTH + 16
+}
+
+fn xml_escape(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+impl TopicTree {
+    pub(crate) fn to_mermaid(&self) -> String {
+        let mut lines = vec![
+            String::from("```mermaid"),
+            String::from("mindmap"),
+            format!("  root(({}))", mermaid_text(&self.root)),
+        ];
+        for (topic, children) in &self.branches {
+            lines.push(format!("    {}", mermaid_text(topic)));
+            for child in children {
+                lines.push(format!("      {}", mermaid_text(child)));
+            }
+        }
+        lines.push(String::from("```"));
+
+        lines.join("\n")
+    }
+
+    /// Lays the tree out left to right as a standalone SVG, for renderers without Mermaid.
+    pub(crate) fn to_svg(&self) -> String {
+        let root_width = text_width(&self.root);
+        let topic_width = self
+            .branches
+            .iter()
+            .map(|(topic, _)| text_width(topic))
+            .max()
+            .unwrap_or(0);
+        let child_width = self
+            .branches
+            .iter()
+            .flat_map(|(_, children)| children)
+            .map(|child| text_width(child))
+            .max()
+            .unwrap_or(0);
+
+        let topic_x = MARGIN + root_width + COLUMN_GAP;
+        let child_x = topic_x + topic_width + COLUMN_GAP;
+
+        // Each branch takes as many rows as it has children, and at least one
+        let rows: usize = self.branches.iter().map(|(_, c)| c.len().max(1)).sum();
+        let height = rows.max(1) * ROW_HEIGHT + 2 * MARGIN;
+        let width = child_x + child_width + MARGIN;
+
+        let node = |x: usize, y: usize, text: &str, class: &str| {
+            format!(
+                "<rect class=\"{}\" x=\"{}\" y=\"{}\" width=\"{}\" height=\"{}\" rx=\"6\"/><text x=\"{}\" y=\"{}\">{}</text>",
+                class,
+                x,
+                y - ROW_HEIGHT / 2 + 3,
+                text_width(text),
+                ROW_HEIGHT - 6,
+                x + 8,
+                y + 4,
+                xml_escape(text)
+            )
+        };
+        let edge = |x1: usize, y1: usize, x2: usize, y2: usize| {
+            let mid = (x1 + x2) / 2;
+            format!(
+                "<path d=\"M{} {} C{} {}, {} {}, {} {}\"/>",
+                x1, y1, mid, y1, mid, y2, x2, y2
+            )
+        };
+
+        let root_y = height / 2;
+        let mut elements = Vec::new();
+        let mut row = 0;
+        for (topic, children) in &self.branches {
+            let span = children.len().max(1);
+            let topic_y = MARGIN + row * ROW_HEIGHT + span * ROW_HEIGHT / 2;
+            elements.push(edge(MARGIN + root_width, root_y, topic_x, topic_y));
+            elements.push(node(topic_x, topic_y, topic, "topic"));
+
+            for (i, child) in children.iter().enumerate() {
+                let child_y = MARGIN + (row + i) * ROW_HEIGHT + ROW_HEIGHT / 2;
+                elements.push(edge(topic_x + text_width(topic), topic_y, child_x, child_y));
+                elements.push(node(child_x, child_y, child, "chunk-topic"));
+            }
+            row += span;
+        }
+        elements.push(node(MARGIN, root_y, &self.root, "root"));
+
+        format!(
+            "<svg xmlns=\"http://www.w3.org/2000/svg\" width=\"{w}\" height=\"{h}\" viewBox=\"0 0 {w} {h}\" font-family=\"sans-serif\" font-size=\"12\">\n<style>path {{ fill: none; stroke: #999; }} rect {{ stroke: #555; }} .root {{ fill: #ffd479; }} .topic {{ fill: #a9d1f7; }} .chunk-topic {{ fill: #eee; }}</style>\n{}\n</svg>\n",
+            elements.join("\n"),
+            w = width,
+            h = height
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_bulleted_and_numbered_topics() {
+        let text = "Here are the main topics:\n\
+                    - **Budget**: the Q3 numbers\n\
+                    * Hiring plan: two backend roles\n\
+                    • Office move.\n\
+                    1. Roadmap\n\
+                    2) **Launch date**";
+
+        assert_eq!(
+            parse_topics(text),
+            [
+                "Budget",
+                "Hiring plan",
+                "Office move",
+                "Roadmap",
+                "Launch date"
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_rules_headings_and_empty_bullets() {
+        let text = "**Topics:**\n\
+                    ---\n\
+                    - Budget\n\
+                    ***\n\
+                    -\n\
+                    - **\n\
+                    *   \n\
+                    - ...\n\
+                    2023 was busy\n\
+                    - Hiring";
+
+        assert_eq!(parse_topics(text), ["Budget", "Hiring"]);
+    }
+
+    #[test]
+    fn truncates_long_topics() {
+        let topic = parse_topics(&format!("- {}", "word ".repeat(20)))
+            .pop()
+            .unwrap();
+
+        assert_eq!(topic.chars().count(), MAX_TOPIC_CHARS);
+        assert!(topic.ends_with("word…"));
+    }
+}
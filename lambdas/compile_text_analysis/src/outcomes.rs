@@ -0,0 +1,268 @@
+use crate::entities::tokenize;
+use crate::locale::Locale;
+use crate::transcript::{chunk_link, position_link, table_cell, ChunkMetadata};
+use serde::Deserialize;
+use std::collections::BTreeSet;
+
+/// What the "Extract Meeting Outcomes" stage returns for one chunk.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct ChunkOutcomes {
+    pub(crate) action_items: Vec<ActionItem>,
+    pub(crate) decisions: Vec<Decision>,
+    pub(crate) open_questions: Vec<OpenQuestion>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct ActionItem {
+    pub(crate) owner: Option<String>,
+    pub(crate) task: String,
+    pub(crate) due: Option<String>,
+    /// A short verbatim phrase from the chunk used to locate where this was said.
+    pub(crate) quote: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct Decision {
+    pub(crate) decision: String,
+    pub(crate) quote: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub(crate) struct OpenQuestion {
+    pub(crate) question: String,
+    pub(crate) quote: Option<String>,
+}
+
+/// Parses the model's reply, tolerating prose or code fences around the JSON object.
+pub(crate) fn parse_outcomes(text: &str) -> Option<ChunkOutcomes> {
+    let start = text.find('{')?;
+    let end = text.rfind('}')?;
+    serde_json::from_str(text.get(start..=end)?).ok()
+}
+
+/// The character offset of a quote in a chunk, ignoring case. Lowercasing the whole text
+/// instead would shift offsets wherever a character lowercases to more than one.
+fn quote_offset(text: &str, quote: &str) -> Option<usize> {
+    let quote: Vec<char> = quote.trim().chars().collect();
+    if quote.is_empty() {
+        return None;
+    }
+
+    let text: Vec<char> = text.chars().collect();
+    text.windows(quote.len()).position(|window| {
+        window
+            .iter()
+            .zip(&quote)
+            .all(|(a, b)| a.to_lowercase().eq(b.to_lowercase()))
+    })
+}
+
+/// Links to where a quote was said, falling back to the start of its chunk.
+fn source_link(
+    chunk: usize,
+    quote: Option<&str>,
+    chunk_texts: &[&str],
+    chunks: &[ChunkMetadata],
+    locale: &Locale,
+) -> String {
+    let offset = quote
+        .zip(chunk_texts.get(chunk))
+        .and_then(|(quote, text)| quote_offset(text, quote));
+
+    match offset {
+        Some(offset) => position_link(chunk, offset, chunks, locale),
+        None => chunk_link(chunk, chunks, locale),
+    }
+}
+
+fn cell(text: Option<&str>) -> String {
+    match text.map(str::trim).filter(|t| !t.is_empty()) {
+        Some(text) => table_cell(text),
+        None => String::from("—"),
+    }
+}
+
+/// Keeps the first of any items whose text only differs in case, punctuation or plurals.
+fn deduplicate<'a, T>(
+    items: impl Iterator<Item = (usize, &'a T)>,
+    text: impl Fn(&T) -> &str,
+) -> Vec<(usize, &'a T)> {
+    let mut seen = BTreeSet::new();
+    items
+        .filter(|(_, item)| seen.insert(tokenize(text(item), true).join(" ")))
+        .collect()
+}
+
+pub(crate) fn format_action_items(
+    outcomes: &[(usize, ChunkOutcomes)],
+    chunk_texts: &[&str],
+    chunks: &[ChunkMetadata],
+    locale: &Locale,
+) -> String {
+    let items = deduplicate(
+        outcomes
+            .iter()
+            .flat_map(|(chunk, o)| o.action_items.iter().map(move |item| (*chunk, item))),
+        |item| &item.task,
+    );
+
+    if items.is_empty() {
+        return locale.no_action_items.to_string();
+    }
+
+    let rows: Vec<String> = items
+        .iter()
+        .map(|(chunk, item)| {
+            format!(
+                "| {} | {} | {} | {} |",
+                cell(item.owner.as_deref()),
+                cell(Some(&item.task)),
+                cell(item.due.as_deref()),
+                source_link(*chunk, item.quote.as_deref(), chunk_texts, chunks, locale)
+            )
+        })
+        .collect();
+
+    format!(
+        "| {} | {} | {} | {} |\n|---|---|---|---|\n{}",
+        locale.owner,
+        locale.task,
+        locale.due,
+        locale.source,
+        rows.join("\n")
+    )
+}
+
+pub(crate) fn format_decisions(
+    outcomes: &[(usize, ChunkOutcomes)],
+    chunk_texts: &[&str],
+    chunks: &[ChunkMetadata],
+    locale: &Locale,
+) -> String {
+    let decisions = deduplicate(
+        outcomes
+            .iter()
+            .flat_map(|(chunk, o)| o.decisions.iter().map(move |d| (*chunk, d))),
+        |decision| &decision.decision,
+    );
+
+    if decisions.is_empty() {
+        return locale.no_decisions.to_string();
+    }
+
+    decisions
+        .iter()
+        .map(|(chunk, decision)| {
+            format!(
+                "- {} ({})",
+                decision.decision.trim(),
+                source_link(
+                    *chunk,
+                    decision.quote.as_deref(),
+                    chunk_texts,
+                    chunks,
+                    locale
+                )
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub(crate) fn format_open_questions(
+    outcomes: &[(usize, ChunkOutcomes)],
+    chunk_texts: &[&str],
+    chunks: &[ChunkMetadata],
+    locale: &Locale,
+) -> String {
+    let questions = deduplicate(
+        outcomes
+            .iter()
+            .flat_map(|(chunk, o)| o.open_questions.iter().map(move |q| (*chunk, q))),
+        |question| &question.question,
+    );
+
+    if questions.is_empty() {
+        return locale.no_open_questions.to_string();
+    }
+
+    questions
+        .iter()
+        .map(|(chunk, question)| {
+            format!(
+                "- {} ({})",
+                question.question.trim(),
+                source_link(
+                    *chunk,
+                    question.quote.as_deref(),
+                    chunk_texts,
+                    chunks,
+                    locale
+                )
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn quotes_are_found_ignoring_case() {
+        let text = "We reviewed the Budget. Then we moved the launch.";
+
+        assert_eq!(quote_offset(text, "reviewed the budget"), Some(3));
+        assert_eq!(quote_offset(text, "  MOVED THE LAUNCH "), Some(32));
+        assert_eq!(quote_offset(text, "cancelled the launch"), None);
+    }
+
+    #[test]
+    fn quote_offsets_count_characters_of_the_original_text() {
+        // `İ` lowercases to two characters, which would shift a search in the lowercased text
+        let text = "İstanbul office: Maria will send the contract.";
+
+        assert_eq!(quote_offset(text, "maria will send"), Some(17));
+        assert_eq!(quote_offset("Café décor était prévu", "ÉTAIT"), Some(11));
+    }
+
+    #[test]
+    fn blank_quotes_are_not_found() {
+        assert_eq!(quote_offset("Anything at all", ""), None);
+        assert_eq!(quote_offset("Anything at all", "   "), None);
+    }
+
+    #[test]
+    fn source_links_fall_back_to_the_chunk() {
+        let locale = Locale::for_language(None);
+        let texts = ["Intro.", "We agreed to ship on Friday."];
+        let chunks = [
+            ChunkMetadata {
+                begin_offset: 0,
+                end_offset: 6,
+                start_time: Some(0.0),
+                ..Default::default()
+            },
+            ChunkMetadata {
+                begin_offset: 7,
+                end_offset: 35,
+                start_time: Some(60.0),
+                time_anchors: vec![(7, 60.0), (35, 88.0)],
+                ..Default::default()
+            },
+        ];
+
+        assert_eq!(
+            source_link(1, Some("ship on friday"), &texts, &chunks, locale),
+            position_link(1, 13, &chunks, locale)
+        );
+        for quote in [None, Some(" "), Some("not said")] {
+            assert_eq!(
+                source_link(1, quote, &texts, &chunks, locale),
+                chunk_link(1, &chunks, locale)
+            );
+        }
+    }
+}
@@ -0,0 +1,133 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::BTreeMap;
+
+/// How the extract-transcript lambda split the transcript.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub(crate) struct ChunkingSettings {
+    /// Chunk sizes are in UTF-8 bytes. Runs from before that recorded them as characters.
+    #[serde(alias = "min_characters")]
+    pub(crate) min_bytes: usize,
+    #[serde(alias = "max_characters")]
+    pub(crate) max_bytes: usize,
+    pub(crate) time_anchor_spacing: usize,
+}
+
+/// Pipeline details gathered by the state machine while preparing the analysis results.
+#[derive(Debug, Deserialize, Default)]
+#[serde(default)]
+pub(crate) struct PipelineDetails {
+    pub(crate) bucket: Option<String>,
+    pub(crate) language_code: Option<String>,
+    /// The model the state machine was configured to invoke.
+    pub(crate) model: Option<String>,
+    /// SHA-256 of each prompt's instructions, keyed by prompt name.
+    pub(crate) prompt_hashes: BTreeMap<String, String>,
+    pub(crate) chunking: Option<ChunkingSettings>,
+    pub(crate) pipeline_version: Option<String>,
+    pub(crate) execution_arn: Option<String>,
+}
+
+/// Everything needed to tell which pipeline produced a report, so reports can be compared across
+/// pipeline changes.
+#[derive(Debug, Serialize)]
+pub(crate) struct Provenance {
+    pub(crate) source: String,
+    pub(crate) language: Option<String>,
+    pub(crate) models: Vec<String>,
+    pub(crate) prompt_hashes: BTreeMap<String, String>,
+    pub(crate) chunking: Option<ChunkingSettings>,
+    pub(crate) chunk_count: usize,
+    pub(crate) pipeline_version: Option<String>,
+    pub(crate) execution_arn: Option<String>,
+    pub(crate) generated_at: String,
+}
+
+impl Provenance {
+    /// `models` are the IDs Bedrock reported in its responses; the configured model is used when
+    /// none were reported.
+    pub(crate) fn new(
+        details: PipelineDetails,
+        key: &str,
+        mut models: Vec<String>,
+        chunk_count: usize,
+        generated_at: String,
+    ) -> Self {
+        models.sort();
+        models.dedup();
+        if models.is_empty() {
+            models.extend(details.model);
+        }
+
+        Self {
+            source: match details.bucket {
+                Some(bucket) => format!("s3://{}/{}", bucket, key),
+                None => key.to_string(),
+            },
+            language: details.language_code,
+            models,
+            prompt_hashes: details.prompt_hashes,
+            chunking: details.chunking,
+            chunk_count,
+            pipeline_version: details.pipeline_version,
+            execution_arn: details.execution_arn,
+            generated_at,
+        }
+    }
+
+    /// Renders the provenance as a YAML front matter block.
+    pub(crate) fn front_matter(&self) -> String {
+        // Field order follows the struct, which serde_json's map would not preserve
+        let fields: [(&str, Value); 9] = [
+            ("source", Value::from(self.source.as_str())),
+            ("language", Value::from(self.language.clone())),
+            ("models", Value::from(self.models.clone())),
+            (
+                "prompt_hashes",
+                serde_json::to_value(&self.prompt_hashes).unwrap_or_default(),
+            ),
+            (
+                "chunking",
+                serde_json::to_value(&self.chunking).unwrap_or_default(),
+            ),
+            ("chunk_count", Value::from(self.chunk_count)),
+            (
+                "pipeline_version",
+                Value::from(self.pipeline_version.clone()),
+            ),
+            ("execution_arn", Value::from(self.execution_arn.clone())),
+            ("generated_at", Value::from(self.generated_at.as_str())),
+        ];
+
+        let lines: Vec<String> = fields
+            .iter()
+            .map(|(name, value)| yaml_field(name, value, 0))
+            .collect();
+
+        format!("---\n{}\n---", lines.join("\n"))
+    }
+}
+
+/// Emits one YAML mapping entry. Scalars are written as JSON, which is valid YAML and keeps
+/// strings safely quoted.
+fn yaml_field(name: &str, value: &Value, indent: usize) -> String {
+    let padding = " ".repeat(indent);
+
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            let entries: Vec<String> = map
+                .iter()
+                .map(|(key, value)| yaml_field(key, value, indent + 2))
+                .collect();
+            format!("{}{}:\n{}", padding, name, entries.join("\n"))
+        }
+        Value::Array(items) if !items.is_empty() => {
+            let entries: Vec<String> = items
+                .iter()
+                .map(|item| format!("{}  - {}", padding, item))
+                .collect();
+            format!("{}{}:\n{}", padding, name, entries.join("\n"))
+        }
+        _ => format!("{}{}: {}", padding, name, value),
+    }
+}
@@ -0,0 +1,516 @@
+use crate::config::SentimentStyle;
+use crate::locale::Locale;
+use crate::transcript::{chunk_anchor, chunk_reference, format_timestamp, ChunkMetadata};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+const SENTIMENT_LABELS: [&str; 4] = ["Positive", "Negative", "Neutral", "Mixed"];
+const SPARKLINE_CHARS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+#[derive(Debug, Deserialize, Clone)]
+#[serde(rename_all = "PascalCase")]
+pub(crate) struct SentimentData {
+    pub(crate) sentiment: String,
+    pub(crate) sentiment_score: BTreeMap<String, f64>,
+}
+
+impl SentimentData {
+    pub(crate) fn score(&self, label: &str) -> f64 {
+        self.sentiment_score.get(label).copied().unwrap_or(0.0)
+    }
+}
+
+pub(crate) fn format_sentiment_score(score: f64) -> String {
+    format!("{:.1}%", score * 100.0)
+}
+
+pub(crate) fn get_sentiment_emoji(sentiment: &str) -> &'static str {
+    match sentiment {
+        "POSITIVE" => "😊",
+        "NEGATIVE" => "😔",
+        "NEUTRAL" => "😐",
+        "MIXED" => "🤔",
+        _ => "",
+    }
+}
+
+/// How per-chunk sentiment is rolled up into the overall verdict.
+#[derive(Debug, Deserialize, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub(crate) enum SentimentAggregation {
+    /// Every chunk casts one vote and contributes equally to the averaged scores.
+    Majority,
+    /// Chunks are weighted by their length in characters.
+    LengthWeighted,
+    /// Chunks are weighted by their length and by the score of their winning label.
+    #[default]
+    ConfidenceWeighted,
+}
+
+impl SentimentAggregation {
+    pub(crate) fn describe(&self, locale: &Locale) -> &'static str {
+        match self {
+            SentimentAggregation::Majority => locale.majority,
+            SentimentAggregation::LengthWeighted => locale.length_weighted,
+            SentimentAggregation::ConfidenceWeighted => locale.confidence_weighted,
+        }
+    }
+}
+
+/// The overall verdict along with the method used to reach it.
+pub(crate) struct CombinedSentiment {
+    pub(crate) data: SentimentData,
+    pub(crate) method: String,
+}
+
+/// Maps a Comprehend label such as `POSITIVE` to its key in `SentimentScore`.
+fn score_key(label: &str) -> Option<&'static str> {
+    SENTIMENT_LABELS
+        .iter()
+        .find(|key| key.eq_ignore_ascii_case(label))
+        .copied()
+}
+
+/// Ties between labels are broken by averaged score, then in this order, most cautious first.
+fn tie_break_rank(label: &str) -> usize {
+    match label {
+        "NEUTRAL" => 0,
+        "MIXED" => 1,
+        "NEGATIVE" => 2,
+        "POSITIVE" => 3,
+        _ => 4,
+    }
+}
+
+pub(crate) fn combine_sentiment_data(
+    sentiments: &[Vec<SentimentData>],
+    chunks: &[ChunkMetadata],
+    strategy: SentimentAggregation,
+    locale: &Locale,
+) -> CombinedSentiment {
+    // Flatten the nested arrays
+    let flattened: Vec<&SentimentData> = sentiments.iter().flat_map(|inner| inner.iter()).collect();
+
+    if flattened.is_empty() {
+        return CombinedSentiment {
+            data: SentimentData {
+                sentiment: "NEUTRAL".to_string(),
+                sentiment_score: BTreeMap::new(),
+            },
+            method: locale.no_sentiment.to_string(),
+        };
+    }
+
+    // Lengths are only usable when every chunk has metadata
+    let lengths_known = strategy != SentimentAggregation::Majority
+        && chunks.len() == flattened.len()
+        && chunks.iter().all(|chunk| chunk.len() > 0);
+
+    let mut votes: BTreeMap<&str, f64> = BTreeMap::new();
+    let mut total_scores: BTreeMap<String, f64> = BTreeMap::new();
+    let mut total_weight = 0.0;
+
+    for (i, sentiment) in flattened.iter().enumerate() {
+        let length = if lengths_known {
+            chunks[i].len() as f64
+        } else {
+            1.0
+        };
+        let weight = match strategy {
+            SentimentAggregation::Majority | SentimentAggregation::LengthWeighted => length,
+            SentimentAggregation::ConfidenceWeighted => {
+                length
+                    * score_key(&sentiment.sentiment)
+                        .map(|key| sentiment.score(key))
+                        .unwrap_or(1.0)
+            }
+        };
+
+        *votes.entry(sentiment.sentiment.as_str()).or_default() += weight;
+        for (key, &score) in &sentiment.sentiment_score {
+            *total_scores.entry(key.clone()).or_default() += score * weight;
+        }
+        total_weight += weight;
+    }
+
+    // Weighted average of the scores
+    let average_scores: BTreeMap<String, f64> = total_scores
+        .into_iter()
+        .map(|(key, total)| {
+            let average = if total_weight > 0.0 {
+                total / total_weight
+            } else {
+                0.0
+            };
+            (key, average)
+        })
+        .collect();
+
+    let average_of = |label: &str| {
+        score_key(label)
+            .and_then(|key| average_scores.get(key))
+            .copied()
+            .unwrap_or(0.0)
+    };
+
+    // Pick the label with the most weight, breaking ties deterministically
+    let overall_sentiment = votes
+        .iter()
+        .max_by(|(a, a_votes), (b, b_votes)| {
+            a_votes
+                .total_cmp(b_votes)
+                .then_with(|| average_of(a).total_cmp(&average_of(b)))
+                .then_with(|| tie_break_rank(b).cmp(&tie_break_rank(a)))
+        })
+        .map(|(sentiment, _)| sentiment.to_string())
+        .unwrap_or_else(|| "NEUTRAL".to_string());
+
+    let mut method = format!(
+        "{} {}",
+        strategy.describe(locale),
+        (locale.across_chunks)(flattened.len())
+    );
+    if strategy != SentimentAggregation::Majority && !lengths_known {
+        method.push_str(&format!(" ({})", locale.lengths_unavailable));
+    }
+
+    CombinedSentiment {
+        data: SentimentData {
+            sentiment: overall_sentiment,
+            sentiment_score: average_scores,
+        },
+        method,
+    }
+}
+
+/// Maps the net sentiment of a chunk (positive minus negative) onto a block character.
+fn sparkline_char(sentiment: &SentimentData) -> char {
+    let net = (sentiment.score("Positive") - sentiment.score("Negative")).clamp(-1.0, 1.0);
+    let level = ((net + 1.0) / 2.0 * (SPARKLINE_CHARS.len() - 1) as f64).round() as usize;
+    SPARKLINE_CHARS[level]
+}
+
+fn describe_position(index: usize, chunk: Option<&ChunkMetadata>, locale: &Locale) -> String {
+    let link = chunk_reference(index, locale);
+
+    match chunk.and_then(|c| c.start_time) {
+        Some(start) => format!("{} ({})", (locale.at_time)(&format_timestamp(start)), link),
+        None => (locale.in_chunk)(&link),
+    }
+}
+
+fn sparkline(sentiments: &[Vec<SentimentData>]) -> String {
+    sentiments.iter().flatten().map(sparkline_char).collect()
+}
+
+fn format_sentiment_timeline(
+    sentiments: &[Vec<SentimentData>],
+    chunks: &[ChunkMetadata],
+    locale: &Locale,
+) -> String {
+    // Comprehend results arrive in chunk order once flattened
+    let timeline: Vec<&SentimentData> = sentiments.iter().flat_map(|inner| inner.iter()).collect();
+
+    if timeline.is_empty() {
+        return locale.no_sentiment.to_string();
+    }
+
+    let rows: Vec<String> = timeline
+        .iter()
+        .enumerate()
+        .map(|(i, sentiment)| {
+            let scores: Vec<String> = SENTIMENT_LABELS
+                .iter()
+                .map(|label| format_sentiment_score(sentiment.score(label)))
+                .collect();
+
+            format!(
+                "| [{}]({}) | {} | {} {} | {} |",
+                i + 1,
+                chunk_anchor(i),
+                chunks
+                    .get(i)
+                    .and_then(ChunkMetadata::time_range)
+                    .unwrap_or_else(|| String::from("—")),
+                get_sentiment_emoji(&sentiment.sentiment),
+                locale.sentiment_label(&sentiment.sentiment),
+                scores.join(" | ")
+            )
+        })
+        .collect();
+
+    let transitions: Vec<String> = timeline
+        .windows(2)
+        .enumerate()
+        .filter(|(_, pair)| pair[0].sentiment != pair[1].sentiment)
+        .map(|(i, pair)| {
+            format!(
+                "- {}",
+                (locale.turned)(
+                    &locale.sentiment_label(&pair[1].sentiment),
+                    &describe_position(i + 1, chunks.get(i + 1), locale)
+                )
+            )
+        })
+        .collect();
+
+    let mut sections = vec![
+        format!(
+            "{}: `{}` ({})",
+            locale.arc,
+            sparkline(sentiments),
+            locale.arc_legend
+        ),
+        format!(
+            "| {} | {} | {} | {} |\n|---|---|---|---|---|---|---|\n{}",
+            locale.chunk,
+            locale.time,
+            locale.sentiment,
+            locale.sentiment_labels.join(" | "),
+            rows.join("\n")
+        ),
+    ];
+
+    if transitions.is_empty() {
+        sections.push((locale.stayed)(
+            &locale.sentiment_label(&timeline[0].sentiment),
+        ));
+    } else {
+        sections.push(format!(
+            "{}:\n{}",
+            locale.transitions,
+            transitions.join("\n")
+        ));
+    }
+
+    sections.join("\n\n")
+}
+
+pub(crate) fn format_sentiment_section(
+    sentiments: &[Vec<SentimentData>],
+    chunks: &[ChunkMetadata],
+    strategy: SentimentAggregation,
+    style: SentimentStyle,
+    locale: &Locale,
+) -> String {
+    let CombinedSentiment { data, method } =
+        combine_sentiment_data(sentiments, chunks, strategy, locale);
+    let heading = format!(
+        "## {} {}",
+        locale.sentiment_analysis,
+        get_sentiment_emoji(&data.sentiment)
+    );
+
+    if style == SentimentStyle::Compact {
+        return format!(
+            "{}\n{}: **{}** · {}: `{}`",
+            heading,
+            locale.overall_sentiment,
+            locale.sentiment_label(&data.sentiment),
+            locale.arc,
+            sparkline(sentiments)
+        );
+    }
+
+    let scores: Vec<String> = SENTIMENT_LABELS
+        .iter()
+        .zip(locale.sentiment_labels)
+        .map(|(key, label)| format!("- {}: {}", label, format_sentiment_score(data.score(key))))
+        .collect();
+
+    let mut section = format!(
+        "{}\n{}: **{}**\n{}: {}\n\n{}:\n{}",
+        heading,
+        locale.overall_sentiment,
+        locale.sentiment_label(&data.sentiment),
+        locale.aggregation,
+        method,
+        locale.confidence_scores,
+        scores.join("\n")
+    );
+
+    if style == SentimentStyle::Full {
+        section.push_str(&format!(
+            "\n\n### {}\n{}",
+            locale.timeline,
+            format_sentiment_timeline(sentiments, chunks, locale)
+        ));
+    }
+
+    section
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sentiment(label: &str, positive: f64, negative: f64, neutral: f64) -> SentimentData {
+        SentimentData {
+            sentiment: label.to_string(),
+            sentiment_score: BTreeMap::from([
+                ("Positive".to_string(), positive),
+                ("Negative".to_string(), negative),
+                ("Neutral".to_string(), neutral),
+                ("Mixed".to_string(), 0.0),
+            ]),
+        }
+    }
+
+    fn chunk(length: usize) -> ChunkMetadata {
+        ChunkMetadata {
+            end_offset: length,
+            ..Default::default()
+        }
+    }
+
+    fn combine(
+        sentiments: &[SentimentData],
+        chunks: &[ChunkMetadata],
+        strategy: SentimentAggregation,
+    ) -> CombinedSentiment {
+        // Comprehend results arrive wrapped in one array per chunk
+        let nested: Vec<Vec<SentimentData>> = sentiments.iter().map(|s| vec![s.clone()]).collect();
+        combine_sentiment_data(&nested, chunks, strategy, Locale::for_language(None))
+    }
+
+    fn verdict(
+        sentiments: &[SentimentData],
+        chunks: &[ChunkMetadata],
+        strategy: SentimentAggregation,
+    ) -> String {
+        combine(sentiments, chunks, strategy).data.sentiment
+    }
+
+    #[test]
+    fn no_sentiment_is_neutral() {
+        let combined = combine(&[], &[], SentimentAggregation::Majority);
+        assert_eq!(combined.data.sentiment, "NEUTRAL");
+        assert!(combined.data.sentiment_score.is_empty());
+    }
+
+    #[test]
+    fn majority_counts_one_vote_per_chunk() {
+        let sentiments = [
+            sentiment("POSITIVE", 0.6, 0.1, 0.3),
+            sentiment("POSITIVE", 0.6, 0.1, 0.3),
+            sentiment("NEGATIVE", 0.0, 1.0, 0.0),
+        ];
+        assert_eq!(
+            verdict(&sentiments, &[], SentimentAggregation::Majority),
+            "POSITIVE"
+        );
+
+        let combined = combine(&sentiments, &[], SentimentAggregation::Majority);
+        assert!((combined.data.score("Negative") - 0.4).abs() < 1e-9);
+    }
+
+    #[test]
+    fn majority_ties_go_to_the_higher_average_score() {
+        let sentiments = [
+            sentiment("POSITIVE", 0.6, 0.4, 0.0),
+            sentiment("NEGATIVE", 0.1, 0.9, 0.0),
+        ];
+        assert_eq!(
+            verdict(&sentiments, &[], SentimentAggregation::Majority),
+            "NEGATIVE"
+        );
+    }
+
+    #[test]
+    fn exact_ties_go_to_the_most_cautious_label_in_any_order() {
+        let positive = sentiment("POSITIVE", 0.5, 0.5, 0.0);
+        let negative = sentiment("NEGATIVE", 0.5, 0.5, 0.0);
+        for sentiments in [[positive.clone(), negative.clone()], [negative, positive]] {
+            assert_eq!(
+                verdict(&sentiments, &[], SentimentAggregation::Majority),
+                "NEGATIVE"
+            );
+        }
+
+        let neutral = SentimentData {
+            sentiment: "NEUTRAL".to_string(),
+            sentiment_score: BTreeMap::new(),
+        };
+        let mixed = SentimentData {
+            sentiment: "MIXED".to_string(),
+            ..neutral.clone()
+        };
+        assert_eq!(
+            verdict(&[mixed, neutral], &[], SentimentAggregation::Majority),
+            "NEUTRAL"
+        );
+    }
+
+    #[test]
+    fn length_weighting_favors_longer_chunks() {
+        let sentiments = [
+            sentiment("POSITIVE", 0.8, 0.1, 0.1),
+            sentiment("NEGATIVE", 0.1, 0.8, 0.1),
+            sentiment("POSITIVE", 0.8, 0.1, 0.1),
+        ];
+        let chunks = [chunk(100), chunk(300), chunk(100)];
+
+        assert_eq!(
+            verdict(&sentiments, &chunks, SentimentAggregation::Majority),
+            "POSITIVE"
+        );
+        let combined = combine(&sentiments, &chunks, SentimentAggregation::LengthWeighted);
+        assert_eq!(combined.data.sentiment, "NEGATIVE");
+        assert!((combined.data.score("Positive") - 0.38).abs() < 1e-9);
+        assert!(!combined
+            .method
+            .contains(Locale::for_language(None).lengths_unavailable));
+    }
+
+    #[test]
+    fn missing_lengths_fall_back_to_equal_weights() {
+        let sentiments = [
+            sentiment("POSITIVE", 0.8, 0.1, 0.1),
+            sentiment("NEGATIVE", 0.1, 0.8, 0.1),
+            sentiment("POSITIVE", 0.8, 0.1, 0.1),
+        ];
+        let combined = combine(
+            &sentiments,
+            &[chunk(100), chunk(300)],
+            SentimentAggregation::LengthWeighted,
+        );
+        assert_eq!(combined.data.sentiment, "POSITIVE");
+        assert!(combined
+            .method
+            .contains(Locale::for_language(None).lengths_unavailable));
+    }
+
+    #[test]
+    fn confidence_weighting_favors_confident_chunks() {
+        let sentiments = [
+            sentiment("POSITIVE", 0.95, 0.0, 0.05),
+            sentiment("NEUTRAL", 0.3, 0.3, 0.4),
+            sentiment("NEUTRAL", 0.3, 0.3, 0.4),
+        ];
+        let chunks = [chunk(100), chunk(100), chunk(100)];
+
+        assert_eq!(
+            verdict(&sentiments, &chunks, SentimentAggregation::Majority),
+            "NEUTRAL"
+        );
+        assert_eq!(
+            verdict(
+                &sentiments,
+                &chunks,
+                SentimentAggregation::ConfidenceWeighted
+            ),
+            "POSITIVE"
+        );
+
+        // Length still counts alongside confidence
+        let long_neutral = [chunk(100), chunk(200), chunk(200)];
+        assert_eq!(
+            verdict(
+                &sentiments,
+                &long_neutral,
+                SentimentAggregation::ConfidenceWeighted
+            ),
+            "NEUTRAL"
+        );
+    }
+}
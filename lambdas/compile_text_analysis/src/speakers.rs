@@ -0,0 +1,277 @@
+use crate::locale::Locale;
+use crate::transcript::{chunk_anchor, format_timestamp, table_cell, ChunkMetadata};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+/// Uninterrupted speech by one speaker, as produced by the extract-transcript lambda.
+#[derive(Debug, Deserialize)]
+pub(crate) struct SpeakerTurn {
+    pub(crate) speaker: String,
+    pub(crate) start_time: f64,
+    pub(crate) end_time: f64,
+    pub(crate) words: usize,
+}
+
+impl SpeakerTurn {
+    fn duration(&self) -> f64 {
+        (self.end_time - self.start_time).max(0.0)
+    }
+}
+
+/// Talk-time statistics for one speaker.
+#[derive(Debug, Default)]
+pub(crate) struct SpeakerStats<'a> {
+    pub(crate) speaker: &'a str,
+    pub(crate) talk_time: f64,
+    pub(crate) turns: usize,
+    pub(crate) words: usize,
+    pub(crate) longest_turn: Option<&'a SpeakerTurn>,
+    /// Turns started while another speaker was still talking.
+    pub(crate) interruptions: usize,
+}
+
+impl SpeakerStats<'_> {
+    fn average_turn(&self) -> f64 {
+        if self.turns == 0 {
+            0.0
+        } else {
+            self.talk_time / self.turns as f64
+        }
+    }
+
+    /// Words per minute of the speaker's own talk time.
+    fn speaking_rate(&self) -> Option<f64> {
+        (self.talk_time > 0.0).then(|| self.words as f64 / (self.talk_time / 60.0))
+    }
+}
+
+pub(crate) fn compute_speaker_stats(turns: &[SpeakerTurn]) -> Vec<SpeakerStats<'_>> {
+    let mut stats: BTreeMap<&str, SpeakerStats> = BTreeMap::new();
+    let mut previous_end: Option<(&str, f64)> = None;
+
+    for turn in turns {
+        let entry = stats
+            .entry(turn.speaker.as_str())
+            .or_insert_with(|| SpeakerStats {
+                speaker: &turn.speaker,
+                ..Default::default()
+            });
+
+        entry.talk_time += turn.duration();
+        entry.turns += 1;
+        entry.words += turn.words;
+        if entry
+            .longest_turn
+            .is_none_or(|longest| turn.duration() > longest.duration())
+        {
+            entry.longest_turn = Some(turn);
+        }
+
+        // Overlapping the latest-ending turn of someone else counts as an interruption
+        if let Some((speaker, end)) = previous_end {
+            if speaker != turn.speaker && turn.start_time < end {
+                entry.interruptions += 1;
+            }
+        }
+        if previous_end.is_none_or(|(_, end)| turn.end_time >= end) {
+            previous_end = Some((&turn.speaker, turn.end_time));
+        }
+    }
+
+    // Most talk time first
+    let mut stats: Vec<SpeakerStats> = stats.into_values().collect();
+    stats.sort_by(|a, b| {
+        b.talk_time
+            .total_cmp(&a.talk_time)
+            .then(a.speaker.cmp(b.speaker))
+    });
+    stats
+}
+
+/// Transcribe labels speakers `spk_0`, `spk_1`, …; these are shown counting from one.
+fn speaker_name(label: &str, locale: &Locale) -> String {
+    match label
+        .strip_prefix("spk_")
+        .and_then(|n| n.parse::<usize>().ok())
+    {
+        Some(n) => (locale.speaker_name)(n + 1),
+        None => table_cell(label),
+    }
+}
+
+fn format_duration(seconds: f64) -> String {
+    let total = seconds.max(0.0).round() as u64;
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+
+    if hours > 0 {
+        format!("{}h {:02}m", hours, minutes)
+    } else if minutes > 0 {
+        format!("{}m {:02}s", minutes, seconds)
+    } else {
+        format!("{}s", seconds)
+    }
+}
+
+/// Links a moment in the recording to the summary of the chunk it falls in.
+fn time_link(seconds: f64, chunks: &[ChunkMetadata]) -> String {
+    let chunk = chunks
+        .iter()
+        .rposition(|chunk| chunk.start_time.is_some_and(|start| start <= seconds));
+
+    match chunk {
+        Some(index) => format!("[{}]({})", format_timestamp(seconds), chunk_anchor(index)),
+        None => format_timestamp(seconds),
+    }
+}
+
+pub(crate) fn format_speaker_analytics(
+    turns: &[SpeakerTurn],
+    chunks: &[ChunkMetadata],
+    locale: &Locale,
+) -> String {
+    let stats = compute_speaker_stats(turns);
+
+    if stats.is_empty() {
+        return locale.no_speakers.to_string();
+    }
+
+    let total_talk_time: f64 = stats.iter().map(|s| s.talk_time).sum();
+    let total_interruptions: usize = stats.iter().map(|s| s.interruptions).sum();
+
+    let rows: Vec<String> = stats
+        .iter()
+        .map(|s| {
+            let share = if total_talk_time > 0.0 {
+                s.talk_time / total_talk_time
+            } else {
+                0.0
+            };
+
+            format!(
+                "| {} | {} | {:.1}% | {} | {} | {} | {} | {} |",
+                speaker_name(s.speaker, locale),
+                format_duration(s.talk_time),
+                share * 100.0,
+                s.turns,
+                format_duration(s.average_turn()),
+                s.longest_turn
+                    .map(|turn| format!(
+                        "{} {}",
+                        format_duration(turn.duration()),
+                        (locale.at_time)(&time_link(turn.start_time, chunks))
+                    ))
+                    .unwrap_or_else(|| String::from("—")),
+                s.interruptions,
+                s.speaking_rate()
+                    .map(|rate| format!("{:.0} {}", rate, locale.words_per_minute))
+                    .unwrap_or_else(|| String::from("—"))
+            )
+        })
+        .collect();
+
+    format!(
+        "{}\n\n| {} | {} | {} | {} | {} | {} | {} | {} |\n|---|---|---|---|---|---|---|---|\n{}",
+        (locale.speaker_summary)(
+            stats.len(),
+            &format_duration(total_talk_time),
+            total_interruptions
+        ),
+        locale.speaker,
+        locale.talk_time,
+        locale.share,
+        locale.turns,
+        locale.average_turn,
+        locale.longest_monologue,
+        locale.interruptions,
+        locale.rate,
+        rows.join("\n")
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn turn(speaker: &str, start_time: f64, end_time: f64, words: usize) -> SpeakerTurn {
+        SpeakerTurn {
+            speaker: speaker.to_string(),
+            start_time,
+            end_time,
+            words,
+        }
+    }
+
+    fn stats_for<'a>(stats: &'a [SpeakerStats<'a>], speaker: &str) -> &'a SpeakerStats<'a> {
+        stats.iter().find(|s| s.speaker == speaker).unwrap()
+    }
+
+    #[test]
+    fn interruptions_count_against_the_latest_ending_turn() {
+        let turns = [
+            turn("spk_0", 0.0, 60.0, 150),
+            // Both start while spk_0 is still talking, even though spk_1 has already stopped
+            turn("spk_1", 10.0, 20.0, 25),
+            turn("spk_2", 30.0, 40.0, 20),
+            turn("spk_1", 50.0, 65.0, 30),
+            // spk_1 ran until 65, so this one waits its turn
+            turn("spk_0", 70.0, 80.0, 20),
+        ];
+        let stats = compute_speaker_stats(&turns);
+
+        assert_eq!(stats_for(&stats, "spk_0").interruptions, 0);
+        assert_eq!(stats_for(&stats, "spk_1").interruptions, 2);
+        assert_eq!(stats_for(&stats, "spk_2").interruptions, 1);
+    }
+
+    #[test]
+    fn overlapping_your_own_turn_is_not_an_interruption() {
+        let turns = [turn("spk_0", 0.0, 30.0, 60), turn("spk_0", 25.0, 40.0, 30)];
+        let stats = compute_speaker_stats(&turns);
+
+        assert_eq!(stats[0].interruptions, 0);
+        assert_eq!(stats[0].turns, 2);
+    }
+
+    #[test]
+    fn stats_total_each_speaker() {
+        let turns = [
+            turn("spk_0", 0.0, 60.0, 150),
+            turn("spk_1", 55.0, 70.0, 30),
+            turn("spk_0", 70.0, 80.0, 25),
+        ];
+        let stats = compute_speaker_stats(&turns);
+
+        // Most talk time first
+        let speakers: Vec<&str> = stats.iter().map(|s| s.speaker).collect();
+        assert_eq!(speakers, ["spk_0", "spk_1"]);
+
+        let first = &stats[0];
+        assert_eq!(first.talk_time, 70.0);
+        assert_eq!(first.turns, 2);
+        assert_eq!(first.words, 175);
+        assert_eq!(first.average_turn(), 35.0);
+        assert_eq!(first.speaking_rate(), Some(150.0));
+
+        let longest = first.longest_turn.unwrap();
+        assert_eq!((longest.start_time, longest.end_time), (0.0, 60.0));
+    }
+
+    #[test]
+    fn longest_turn_keeps_the_first_of_equal_turns() {
+        let turns = [turn("spk_0", 0.0, 10.0, 20), turn("spk_0", 20.0, 30.0, 20)];
+        let stats = compute_speaker_stats(&turns);
+
+        assert_eq!(stats[0].longest_turn.unwrap().start_time, 0.0);
+    }
+
+    #[test]
+    fn no_talk_time_has_no_speaking_rate() {
+        let turns = [turn("spk_0", 5.0, 5.0, 3), turn("spk_1", 9.0, 4.0, 2)];
+        let stats = compute_speaker_stats(&turns);
+
+        for speaker in &stats {
+            assert_eq!(speaker.talk_time, 0.0);
+            assert_eq!(speaker.speaking_rate(), None);
+        }
+    }
+}
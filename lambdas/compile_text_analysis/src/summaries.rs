@@ -0,0 +1,126 @@
+use crate::locale::Locale;
+use crate::transcript::{chunk_anchor, ChunkMetadata};
+use crate::{extract_text, ChunkAnalysis};
+
+/// The name reports are written under, relative to the recording's own file name.
+fn file_name(key: &str, suffix: &str) -> String {
+    format!("{}-{}", key.rsplit('/').next().unwrap_or(key), suffix)
+}
+
+pub(crate) fn report_file_name(key: &str) -> String {
+    file_name(key, "report.md")
+}
+
+pub(crate) fn appendix_file_name(key: &str) -> String {
+    file_name(key, "appendix.md")
+}
+
+/// The time range after a chunk's heading, when one is known.
+fn time_suffix(index: usize, chunks: &[ChunkMetadata]) -> String {
+    chunks
+        .get(index)
+        .and_then(ChunkMetadata::time_range)
+        .map(|range| format!(" ({})", range))
+        .unwrap_or_default()
+}
+
+/// One line per chunk linking to its summary.
+fn table_of_contents(count: usize, chunks: &[ChunkMetadata], locale: &Locale) -> String {
+    (0..count)
+        .map(|i| {
+            format!(
+                "- [{}]({}){}",
+                (locale.chunk_summary)(i + 1),
+                chunk_anchor(i),
+                time_suffix(i, chunks)
+            )
+        })
+        .collect::<Vec<String>>()
+        .join("\n")
+}
+
+pub(crate) fn format_chunk_summaries(
+    chunk_summaries: &[ChunkAnalysis],
+    chunks: &[ChunkMetadata],
+    collapse: bool,
+    locale: &Locale,
+) -> String {
+    let summaries: Vec<String> = chunk_summaries
+        .iter()
+        .enumerate()
+        .map(|(i, chunk)| {
+            let summary = extract_text(&chunk.chunk_analysis[0]);
+            let topics = extract_text(&chunk.chunk_analysis[1]);
+            // Links point at a fixed anchor since the heading itself is translated
+            let anchor = format!("<a id=\"{}\"></a>", &chunk_anchor(i)[1..]);
+
+            if collapse {
+                format!(
+                    "{}\n<details>\n<summary><strong>{}</strong>{}</summary>\n\n{}\n\n#### {}\n{}\n\n</details>",
+                    anchor,
+                    (locale.chunk_summary)(i + 1),
+                    time_suffix(i, chunks),
+                    summary.trim(),
+                    locale.topics,
+                    topics.trim()
+                )
+            } else {
+                format!(
+                    "{}\n\n### {}\n{}\n\n#### {}\n{}",
+                    anchor,
+                    (locale.chunk_summary)(i + 1),
+                    summary.trim(),
+                    locale.topics,
+                    topics.trim()
+                )
+            }
+        })
+        .collect();
+
+    format!(
+        "{}\n\n{}",
+        table_of_contents(chunk_summaries.len(), chunks, locale),
+        summaries.join("\n\n")
+    )
+}
+
+/// Stands in for the chunk summaries when they're moved to the appendix. Each entry keeps the
+/// chunk's anchor, so links elsewhere in the report land on the entry pointing into the appendix.
+pub(crate) fn format_appendix_contents(
+    count: usize,
+    chunks: &[ChunkMetadata],
+    key: &str,
+    locale: &Locale,
+) -> String {
+    let appendix = appendix_file_name(key);
+    let entries: Vec<String> = (0..count)
+        .map(|i| {
+            format!(
+                "- <a id=\"{}\"></a>[{}](<{}{}>){}",
+                &chunk_anchor(i)[1..],
+                (locale.chunk_summary)(i + 1),
+                appendix,
+                chunk_anchor(i),
+                time_suffix(i, chunks)
+            )
+        })
+        .collect();
+
+    format!(
+        "{}\n\n{}",
+        (locale.appendix_note)(&appendix),
+        entries.join("\n")
+    )
+}
+
+/// The chunk summaries as a document of their own, linking back to the report.
+pub(crate) fn format_appendix(key: &str, summaries: &str, locale: &Locale) -> String {
+    format!(
+        "# {}: {}\n\n[← {}](<{}>)\n\n{}\n",
+        locale.chunk_summaries,
+        key,
+        locale.back_to_report,
+        report_file_name(key),
+        summaries
+    )
+}
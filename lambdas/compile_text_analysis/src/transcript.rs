@@ -0,0 +1,168 @@
+use crate::locale::Locale;
+use serde::Deserialize;
+
+/// Where a chunk sits in the full transcript, as produced by the extract-transcript lambda.
+#[derive(Debug, Deserialize, Clone, Default)]
+pub(crate) struct ChunkMetadata {
+    pub(crate) begin_offset: usize,
+    pub(crate) end_offset: usize,
+    pub(crate) start_time: Option<f64>,
+    pub(crate) end_time: Option<f64>,
+    /// Sampled `(offset, seconds)` pairs for locating positions inside the chunk.
+    #[serde(default)]
+    pub(crate) time_anchors: Vec<(usize, f64)>,
+    /// Mean Transcribe confidence of the chunk's words.
+    #[serde(default)]
+    pub(crate) confidence: Option<f64>,
+}
+
+impl ChunkMetadata {
+    pub(crate) fn len(&self) -> usize {
+        self.end_offset.saturating_sub(self.begin_offset)
+    }
+
+    /// Estimates when the character at a global transcript offset was spoken by interpolating
+    /// between the surrounding time anchors.
+    pub(crate) fn time_at(&self, offset: usize) -> Option<f64> {
+        let next = self.time_anchors.partition_point(|&(o, _)| o <= offset);
+
+        match (
+            next.checked_sub(1).map(|i| self.time_anchors[i]),
+            self.time_anchors.get(next),
+        ) {
+            (Some((before, before_time)), Some(&(after, after_time))) => {
+                let fraction = (offset - before) as f64 / (after - before) as f64;
+                Some(before_time + fraction * (after_time - before_time))
+            }
+            (Some((_, time)), None) => Some(time),
+            _ => self.start_time,
+        }
+    }
+
+    pub(crate) fn time_range(&self) -> Option<String> {
+        match (self.start_time, self.end_time) {
+            (Some(start), Some(end)) => Some(format!(
+                "{}–{}",
+                format_timestamp(start),
+                format_timestamp(end)
+            )),
+            (Some(start), None) => Some(format_timestamp(start)),
+            _ => None,
+        }
+    }
+}
+
+pub(crate) fn format_timestamp(seconds: f64) -> String {
+    let total = seconds.max(0.0) as u64;
+    let (hours, minutes, seconds) = (total / 3600, (total % 3600) / 60, total % 60);
+
+    if hours > 0 {
+        format!("{}:{:02}:{:02}", hours, minutes, seconds)
+    } else {
+        format!("{:02}:{:02}", minutes, seconds)
+    }
+}
+
+/// Text safe to place in a Markdown table cell: pipes are escaped and line breaks become spaces.
+pub(crate) fn table_cell(text: &str) -> String {
+    text.replace('|', "\\|")
+        .replace("\r\n", " ")
+        .replace(['\r', '\n'], " ")
+}
+
+pub(crate) fn chunk_anchor(index: usize) -> String {
+    format!("#chunk-{}-summary", index + 1)
+}
+
+/// Links to a chunk's summary, labelled with its number.
+pub(crate) fn chunk_reference(index: usize, locale: &Locale) -> String {
+    format!(
+        "[{}]({})",
+        (locale.chunk_label)(index + 1),
+        chunk_anchor(index)
+    )
+}
+
+/// Links to a chunk's summary, labelled with its start time when one is known.
+pub(crate) fn chunk_link(index: usize, chunks: &[ChunkMetadata], locale: &Locale) -> String {
+    match chunks.get(index).and_then(|c| c.start_time) {
+        Some(start) => format!("[{}]({})", format_timestamp(start), chunk_anchor(index)),
+        None => chunk_reference(index, locale),
+    }
+}
+
+/// Links to the chunk containing a position, labelled with the interpolated time it was said and
+/// titled with its offset in the full transcript. `offset` is relative to the start of the chunk.
+pub(crate) fn position_link(
+    index: usize,
+    offset: usize,
+    chunks: &[ChunkMetadata],
+    locale: &Locale,
+) -> String {
+    let Some(metadata) = chunks.get(index) else {
+        return chunk_link(index, chunks, locale);
+    };
+
+    let offset = metadata.begin_offset + offset;
+    match metadata.time_at(offset) {
+        Some(time) => format!(
+            "[{}]({} \"{} {}\")",
+            format_timestamp(time),
+            chunk_anchor(index),
+            locale.transcript_offset,
+            offset
+        ),
+        None => chunk_link(index, chunks, locale),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn chunk(time_anchors: Vec<(usize, f64)>) -> ChunkMetadata {
+        ChunkMetadata {
+            begin_offset: 100,
+            end_offset: 400,
+            start_time: Some(12.0),
+            end_time: Some(48.0),
+            time_anchors,
+            confidence: None,
+        }
+    }
+
+    #[test]
+    fn time_at_interpolates_between_anchors() {
+        let chunk = chunk(vec![(100, 12.0), (200, 20.0), (400, 48.0)]);
+
+        assert_eq!(chunk.time_at(150), Some(16.0));
+        assert_eq!(chunk.time_at(250), Some(27.0));
+        assert_eq!(chunk.time_at(200), Some(20.0));
+    }
+
+    #[test]
+    fn time_at_before_the_first_anchor_is_the_chunk_start() {
+        let chunk = chunk(vec![(150, 15.0), (300, 30.0)]);
+
+        assert_eq!(chunk.time_at(120), Some(12.0));
+    }
+
+    #[test]
+    fn time_at_after_the_last_anchor_is_the_last_anchor() {
+        let chunk = chunk(vec![(150, 15.0), (300, 30.0)]);
+
+        assert_eq!(chunk.time_at(300), Some(30.0));
+        assert_eq!(chunk.time_at(390), Some(30.0));
+    }
+
+    #[test]
+    fn time_at_without_anchors_is_the_chunk_start() {
+        assert_eq!(chunk(Vec::new()).time_at(250), Some(12.0));
+
+        let untimed = ChunkMetadata {
+            start_time: None,
+            ..chunk(Vec::new())
+        };
+        assert_eq!(untimed.time_at(250), None);
+    }
+}
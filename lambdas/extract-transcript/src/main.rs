@@ -3,20 +3,74 @@ use aws_sdk_s3::Client;
 use lambda_runtime::{run, service_fn, Error, LambdaEvent};
 use serde::{Deserialize, Serialize};
 use std::ops::Range;
-use text_splitter::TextSplitter;
+use text_splitter::{ChunkConfig, ChunkSizer, TextSplitter};
 
-const CHUNK_SIZE_RANGE: Range<usize> = 4500..4900; // Slightly under 5000 to be safe
+/// In UTF-8 bytes, slightly under the 5,000 bytes Comprehend's DetectSentiment accepts.
+const CHUNK_SIZE_RANGE: Range<usize> = 4500..4900;
+const TIME_ANCHOR_SPACING: usize = 10; // Words between time anchors, keeps the state payload small
+
+/// Languages Comprehend can detect entities, key phrases and sentiment in.
+const COMPREHEND_LANGUAGES: [&str; 12] = [
+    "ar", "de", "en", "es", "fr", "hi", "it", "ja", "ko", "pt", "zh", "zh-TW",
+];
+const FALLBACK_LANGUAGE: &str = "en";
 
 #[derive(Debug, Deserialize)]
 struct TranscriptInput {
     bucket: String,
     key: String,
+    /// The Transcribe locale the audio was transcribed in, such as `es-US`.
+    #[serde(rename = "languageCode")]
+    language_code: Option<String>,
 }
 
 #[derive(Debug, Serialize)]
 struct TranscriptOutput {
     full_text: String,
     chunks: Vec<String>,
+    chunk_metadata: Vec<ChunkMetadata>,
+    speaker_turns: Vec<SpeakerTurn>,
+    chunking: ChunkingSettings,
+    language: AnalysisLanguage,
+}
+
+/// The Comprehend language the chunks should be analyzed in.
+#[derive(Debug, Serialize)]
+struct AnalysisLanguage {
+    transcribe: Option<String>,
+    comprehend: String,
+    /// Set when Comprehend doesn't support the transcript's language and English is used instead.
+    fallback: bool,
+}
+
+/// The splitter settings, recorded so reports can be traced back to how they were chunked.
+#[derive(Debug, Serialize)]
+struct ChunkingSettings {
+    min_bytes: usize,
+    max_bytes: usize,
+    time_anchor_spacing: usize,
+}
+
+/// Where a chunk sits in the full transcript, as character offsets and seconds into the audio.
+#[derive(Debug, Serialize)]
+struct ChunkMetadata {
+    begin_offset: usize,
+    end_offset: usize,
+    start_time: Option<f64>,
+    end_time: Option<f64>,
+    /// Sampled `(offset, seconds)` pairs for locating positions inside the chunk.
+    time_anchors: Vec<(usize, f64)>,
+    /// Mean Transcribe confidence of the chunk's words.
+    confidence: Option<f64>,
+}
+
+/// Uninterrupted speech by one speaker, merged from consecutive Transcribe segments.
+#[derive(Debug, Serialize)]
+struct SpeakerTurn {
+    speaker: String,
+    start_time: f64,
+    end_time: f64,
+    words: usize,
 }
 
 #[derive(Debug, Deserialize)]
@@ -27,6 +81,23 @@ struct TranscribeOutput {
 #[derive(Debug, Deserialize)]
 struct TranscribeResults {
     transcripts: Vec<Transcript>,
+    #[serde(default)]
+    items: Vec<TranscribeItem>,
+    speaker_labels: Option<SpeakerLabels>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpeakerLabels {
+    segments: Vec<SpeakerSegment>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SpeakerSegment {
+    speaker_label: String,
+    start_time: String,
+    end_time: String,
+    #[serde(default)]
+    items: Vec<serde_json::Value>,
 }
 
 #[derive(Debug, Deserialize)]
@@ -34,12 +105,175 @@ struct Transcript {
     transcript: String,
 }
 
+#[derive(Debug, Deserialize)]
+struct TranscribeItem {
+    start_time: Option<String>,
+    end_time: Option<String>,
+    alternatives: Vec<Alternative>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Alternative {
+    content: String,
+    confidence: Option<String>,
+}
+
 #[derive(Debug, Serialize)]
 struct Response {
-    statusCode: i32,
+    #[serde(rename = "statusCode")]
+    status_code: i32,
     body: TranscriptOutput,
 }
 
+/// A timed word located in the full transcript by character offset.
+struct WordTiming {
+    offset: usize,
+    start_time: f64,
+    end_time: f64,
+    confidence: Option<f64>,
+}
+
+fn word_timings(full_text: &str, items: &[TranscribeItem]) -> Vec<WordTiming> {
+    let mut timings = Vec::new();
+    let mut cursor = 0;
+    let mut char_offset = 0;
+
+    for item in items {
+        // Punctuation items carry no timing information
+        let (Some(start_time), Some(end_time)) = (
+            item.start_time.as_deref().and_then(|t| t.parse().ok()),
+            item.end_time.as_deref().and_then(|t| t.parse().ok()),
+        ) else {
+            continue;
+        };
+        let Some(alternative) = item.alternatives.first() else {
+            continue;
+        };
+        let content = alternative.content.as_str();
+        let Some(position) = full_text[cursor..].find(content) else {
+            continue;
+        };
+
+        char_offset += full_text[cursor..cursor + position].chars().count();
+        timings.push(WordTiming {
+            offset: char_offset,
+            start_time,
+            end_time,
+            confidence: alternative
+                .confidence
+                .as_deref()
+                .and_then(|c| c.parse().ok()),
+        });
+
+        cursor += position + content.len();
+        char_offset += content.chars().count();
+    }
+
+    timings
+}
+
+/// Maps a Transcribe locale to the Comprehend language code for it, falling back to English.
+fn analysis_language(locale: Option<&str>) -> AnalysisLanguage {
+    let comprehend = locale.and_then(|locale| {
+        let mut subtags = locale.split(['-', '_']);
+        let language = subtags.next()?.to_ascii_lowercase();
+        let region = subtags.next().map(str::to_ascii_uppercase);
+
+        // Comprehend distinguishes traditional Chinese, which Transcribe only labels by region
+        let code = match (language.as_str(), region.as_deref()) {
+            ("zh", Some("TW" | "HK")) => String::from("zh-TW"),
+            _ => language,
+        };
+        COMPREHEND_LANGUAGES
+            .contains(&code.as_str())
+            .then_some(code)
+    });
+
+    AnalysisLanguage {
+        transcribe: locale.map(str::to_string),
+        fallback: comprehend.is_none(),
+        comprehend: comprehend.unwrap_or_else(|| FALLBACK_LANGUAGE.to_string()),
+    }
+}
+
+fn speaker_turns(labels: Option<&SpeakerLabels>) -> Vec<SpeakerTurn> {
+    let mut turns: Vec<SpeakerTurn> = Vec::new();
+
+    for segment in labels.map(|l| l.segments.as_slice()).unwrap_or_default() {
+        let (Ok(start_time), Ok(end_time)) = (segment.start_time.parse(), segment.end_time.parse())
+        else {
+            continue;
+        };
+
+        match turns.last_mut() {
+            Some(turn) if turn.speaker == segment.speaker_label => {
+                turn.end_time = turn.end_time.max(end_time);
+                turn.words += segment.items.len();
+            }
+            _ => turns.push(SpeakerTurn {
+                speaker: segment.speaker_label.clone(),
+                start_time,
+                end_time,
+                words: segment.items.len(),
+            }),
+        }
+    }
+
+    turns
+}
+
+/// Measures chunks in UTF-8 bytes, which is how Comprehend limits its input. In Japanese or Hindi
+/// a character takes three bytes, so counting characters would overshoot the limit.
+struct Utf8Bytes;
+
+impl ChunkSizer for Utf8Bytes {
+    fn size(&self, chunk: &str) -> usize {
+        chunk.len()
+    }
+}
+
+/// Splits the transcript into chunks Comprehend accepts, with their byte offsets.
+fn split_chunks(full_text: &str) -> Vec<(usize, &str)> {
+    TextSplitter::new(ChunkConfig::new(CHUNK_SIZE_RANGE).with_sizer(Utf8Bytes))
+        .chunk_indices(full_text)
+        .collect()
+}
+
+fn chunk_metadata(
+    full_text: &str,
+    byte_offset: usize,
+    chunk: &str,
+    words: &[WordTiming],
+) -> ChunkMetadata {
+    let begin_offset = full_text[..byte_offset].chars().count();
+    let end_offset = begin_offset + chunk.chars().count();
+
+    let chunk_words: Vec<&WordTiming> = words
+        .iter()
+        .filter(|word| word.offset >= begin_offset && word.offset < end_offset)
+        .collect();
+
+    let mut time_anchors: Vec<(usize, f64)> = chunk_words
+        .iter()
+        .step_by(TIME_ANCHOR_SPACING)
+        .chain(chunk_words.last())
+        .map(|word| (word.offset, word.start_time))
+        .collect();
+    time_anchors.dedup();
+
+    let confidences: Vec<f64> = chunk_words.iter().filter_map(|w| w.confidence).collect();
+
+    ChunkMetadata {
+        begin_offset,
+        end_offset,
+        start_time: chunk_words.first().map(|word| word.start_time),
+        end_time: chunk_words.last().map(|word| word.end_time),
+        time_anchors,
+        confidence: (!confidences.is_empty())
+            .then(|| confidences.iter().sum::<f64>() / confidences.len() as f64),
+    }
+}
+
 async fn function_handler(event: LambdaEvent<TranscriptInput>) -> Result<Response, Error> {
     let config = aws_config::load_from_env().await;
     let s3_client = Client::new(&config);
@@ -72,15 +306,28 @@ async fn function_handler(event: LambdaEvent<TranscriptInput>) -> Result<Respons
         .map(|t| t.transcript.clone())
         .unwrap_or_default();
 
-    let chunks = TextSplitter::new(CHUNK_SIZE_RANGE)
-        .chunks(&full_text)
-        .map(|c| c.to_string())
-        .collect();
+    let words = word_timings(&full_text, &transcript.results.items);
 
-    let transcript_output = TranscriptOutput { full_text, chunks };
+    let (chunks, chunk_metadata) = split_chunks(&full_text)
+        .into_iter()
+        .map(|(offset, c)| (c.to_string(), chunk_metadata(&full_text, offset, c, &words)))
+        .unzip();
+
+    let transcript_output = TranscriptOutput {
+        full_text,
+        chunks,
+        chunk_metadata,
+        speaker_turns: speaker_turns(transcript.results.speaker_labels.as_ref()),
+        chunking: ChunkingSettings {
+            min_bytes: CHUNK_SIZE_RANGE.start,
+            max_bytes: CHUNK_SIZE_RANGE.end,
+            time_anchor_spacing: TIME_ANCHOR_SPACING,
+        },
+        language: analysis_language(event.payload.language_code.as_deref()),
+    };
 
     Ok(Response {
-        statusCode: 200,
+        status_code: 200,
         body: transcript_output,
     })
 }
@@ -95,3 +342,35 @@ async fn main() -> Result<(), Error> {
 
     run(service_fn(function_handler)).await
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const COMPREHEND_MAX_BYTES: usize = 5000;
+
+    #[test]
+    fn chunks_of_multibyte_text_fit_comprehend() {
+        // Three bytes a character, so 4,900 characters would be 14,700 bytes
+        let full_text = "今日の会議では新しい製品の発売日について話し合いました。".repeat(400);
+        let chunks = split_chunks(&full_text);
+
+        assert!(chunks.len() > 1);
+        for (offset, chunk) in &chunks {
+            assert!(chunk.len() <= CHUNK_SIZE_RANGE.end, "{} bytes", chunk.len());
+            assert!(chunk.len() < COMPREHEND_MAX_BYTES);
+            assert_eq!(&full_text[*offset..*offset + chunk.len()], *chunk);
+        }
+    }
+
+    #[test]
+    fn chunks_of_accented_text_fit_comprehend() {
+        let full_text =
+            "La reunión terminó con una decisión: más pruebas después del lanzamiento. "
+                .repeat(300);
+
+        for (_, chunk) in split_chunks(&full_text) {
+            assert!(chunk.len() <= CHUNK_SIZE_RANGE.end, "{} bytes", chunk.len());
+        }
+    }
+}